@@ -1,4 +1,5 @@
 use std::marker::PhantomData;
+use std::cmp::Ordering;
 use std::fmt;
 
 use error::Error;
@@ -7,13 +8,17 @@ use declarative::DeclResult;
 use declarative::DeclarativeWithArgs;
 use declarative::StaticEncodingSize;
 use declarative::DynamicEncodingSize;
+use owned::IntoOwned;
 
 
 // TODO:
 //  [ ] Implement linear search?
-//  [ ] Implement binary search?
-//  [ ] Implement .get(usize) interface?
 
+/// `length` elements of a static size, which are parsed lazily.  Parsing
+/// an `Array` checks that the elements are present and continues after
+/// them, so that whatever follows the array is parsed from the right place.
+/// Elements are always `Item::SIZE` bytes apart, however many bytes parsing
+/// one of them consumes, so `get` and `iter` agree.
 pub struct Array<'buf, Item>
 where
     Item: DeclarativeWithArgs<'buf>,
@@ -35,6 +40,7 @@ where
 
 impl<'buf, Item> DeclarativeWithArgs<'buf> for Array<'buf, Item>
 where
+    Item: StaticEncodingSize,
     Item: DeclarativeWithArgs<'buf>,
 {
     type Argument = (usize, Item::Argument);
@@ -42,6 +48,8 @@ where
         buffer: &'buf [u8],
         arguments: (usize, Item::Argument),
     ) -> DeclResult<'buf, Self> {
+        let rest = skip_elements::<Item>(buffer, arguments.0)?;
+
         Ok((
             Array {
                 buffer: buffer,
@@ -49,11 +57,113 @@ where
                 argument: arguments.1,
                 phantom: PhantomData,
             },
-            buffer,
+            rest,
         ))
     }
 }
 
+// Returns the buffer following `length` elements of `Item`.  The element
+// buffer itself is kept whole, so that elements may refer past the array.
+fn skip_elements<'buf, Item>(
+    buffer: &'buf [u8],
+    length: usize,
+) -> Result<&'buf [u8], Error>
+where
+    Item: StaticEncodingSize,
+{
+    let size = match Item::SIZE.checked_mul(length) {
+        Some(size) => size,
+        None => return Err(Error::InsufficientBytes),
+    };
+
+    if buffer.len() < size {
+        return Err(Error::InsufficientBytes);
+    }
+
+    Ok(&buffer[size..])
+}
+
+impl<'buf, Item> Array<'buf, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Parses the element at `index`, or returns `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Result<Item, Error>> {
+        if self.length <= index {
+            return None;
+        }
+
+        let mut buffer = &self.buffer[index * Item::SIZE..];
+        Some(buffer.parse_with::<Item>(self.argument.clone()))
+    }
+
+    pub fn iter(&self) -> ArrayIter<'buf, Item> {
+        ArrayIter {
+            buffer: self.buffer,
+            length: self.length,
+            argument: self.argument.clone(),
+            cursor: 0usize,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Parses every element, failing with the first error.
+    pub fn to_vec(&self) -> Result<Vec<Item>, Error> {
+        self.iter().collect_checked()
+    }
+
+    /// Binary searches the array with a comparator, in the manner of
+    /// `slice::binary_search_by`.  Elements are parsed as they are probed,
+    /// and the first parse failure is returned.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<Result<usize, usize>, Error>
+    where
+        F: FnMut(&Item) -> Ordering,
+    {
+        let mut low = 0usize;
+        let mut high = self.length;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let item = match self.get(mid) {
+                Some(item) => item?,
+                None => unreachable!(),
+            };
+
+            match f(&item) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(Ok(mid)),
+            }
+        }
+
+        Ok(Err(low))
+    }
+
+    pub fn binary_search_by_key<K, F>(&self, key: &K, mut f: F) -> Result<Result<usize, usize>, Error>
+    where
+        K: Ord,
+        F: FnMut(&Item) -> K,
+    {
+        self.binary_search_by(|item| f(item).cmp(key))
+    }
+
+    pub fn binary_search(&self, item: &Item) -> Result<Result<usize, usize>, Error>
+    where
+        Item: Ord,
+    {
+        self.binary_search_by(|probe| probe.cmp(item))
+    }
+}
+
 impl<'buf, Item> DynamicEncodingSize for Array<'buf, Item>
 where
     Item: StaticEncodingSize,
@@ -64,6 +174,20 @@ where
     }
 }
 
+impl<'buf, Item> IntoOwned for Array<'buf, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    Item::Argument: Clone,
+{
+    type Owned = Vec<Item::Owned>;
+
+    fn into_owned(self) -> Result<Vec<Item::Owned>, Error> {
+        self.iter().map(|item| item?.into_owned()).collect_checked()
+    }
+}
+
 impl<'buf, Item> IntoIterator for Array<'buf, Item>
 where
     Item: StaticEncodingSize,
@@ -83,6 +207,25 @@ where
     }
 }
 
+/// Collects the elements of an iterator over parsed items, failing with the
+/// first error.  Unlike `collect`, the `Vec` is allocated up front for as
+/// many elements as the iterator knows of, such as the length of an `Array`.
+pub trait CollectChecked<Item>: Iterator<Item = Result<Item, Error>> + Sized {
+    fn collect_checked(self) -> Result<Vec<Item>, Error> {
+        let mut items = Vec::with_capacity(self.size_hint().0);
+        for item in self {
+            items.push(item?);
+        }
+        Ok(items)
+    }
+}
+
+impl<Iter, Item> CollectChecked<Item> for Iter
+where
+    Iter: Iterator<Item = Result<Item, Error>>,
+{
+}
+
 pub struct ArrayIter<'buf, Item>
 where
     Item: DeclarativeWithArgs<'buf>,
@@ -107,9 +250,164 @@ where
         }
 
         self.cursor += 1;
-        let dest = self.buffer.parse_with::<Item>(self.argument.clone());
+        let mut buffer = self.buffer;
+        let dest = buffer.parse_with::<Item>(self.argument.clone());
+        self.buffer = &self.buffer[Item::SIZE..];
         Some(dest)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.cursor;
+        (remaining, Some(remaining))
+    }
+}
+
+/// A `rows × cols` array stored in row-major order, such as the class matrix
+/// of a GPOS pair adjustment format 2 subtable.
+pub struct Array2<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    buffer: &'buf [u8],
+    rows: usize,
+    cols: usize,
+    argument: Item::Argument,
+    phantom: PhantomData<Item>,
+}
+
+impl<'buf, Item> fmt::Debug for Array2<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Array2 {{ rows: {}, cols: {} }}", self.rows, self.cols)
+    }
+}
+
+impl<'buf, Item> DeclarativeWithArgs<'buf> for Array2<'buf, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Argument = (usize, usize, Item::Argument);
+    fn parse_with(
+        buffer: &'buf [u8],
+        arguments: (usize, usize, Item::Argument),
+    ) -> DeclResult<'buf, Self> {
+        let length = match arguments.0.checked_mul(arguments.1) {
+            Some(length) => length,
+            None => return Err(Error::InsufficientBytes),
+        };
+        let rest = skip_elements::<Item>(buffer, length)?;
+
+        Ok((
+            Array2 {
+                buffer: buffer,
+                rows: arguments.0,
+                cols: arguments.1,
+                argument: arguments.2,
+                phantom: PhantomData,
+            },
+            rest,
+        ))
+    }
+}
+
+impl<'buf, Item> Array2<'buf, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Parses the element at `(row, col)`, or returns `None` if either is
+    /// out of bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<Result<Item, Error>> {
+        if self.cols <= col {
+            return None;
+        }
+
+        self.row(row).and_then(|row| row.get(col))
+    }
+
+    /// The elements of `row` as an `Array`, or `None` if it is out of bounds.
+    pub fn row(&self, row: usize) -> Option<Array<'buf, Item>> {
+        if self.rows <= row {
+            return None;
+        }
+
+        Some(Array {
+            buffer: &self.buffer[row * self.cols * Item::SIZE..],
+            length: self.cols,
+            argument: self.argument.clone(),
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn iter_rows(&self) -> Array2Rows<'buf, '_, Item> {
+        Array2Rows {
+            array: self,
+            cursor: 0usize,
+        }
+    }
+}
+
+impl<'buf, Item> DynamicEncodingSize for Array2<'buf, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn size(&self) -> usize {
+        Item::SIZE * self.rows * self.cols
+    }
+}
+
+impl<'buf, Item> IntoOwned for Array2<'buf, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    Item::Argument: Clone,
+{
+    type Owned = Vec<Vec<Item::Owned>>;
+
+    fn into_owned(self) -> Result<Vec<Vec<Item::Owned>>, Error> {
+        self.iter_rows().map(IntoOwned::into_owned).collect()
+    }
+}
+
+pub struct Array2Rows<'buf, 'a, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item: 'a,
+    Item::Argument: 'a,
+    'buf: 'a,
+{
+    array: &'a Array2<'buf, Item>,
+    cursor: usize,
+}
+
+impl<'buf, 'a, Item> Iterator for Array2Rows<'buf, 'a, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    type Item = Array<'buf, Item>;
+    fn next(&mut self) -> Option<Array<'buf, Item>> {
+        let dest = self.array.row(self.cursor);
+        if dest.is_some() {
+            self.cursor += 1;
+        }
+        dest
+    }
 }
 
 /// An unbounded variant of an `Array`.  This type acts like a `&[T]` except that it
@@ -153,3 +451,251 @@ where
         ))
     }
 }
+
+/// The boxed argument producer used by `IndexedArray` by default.  This is
+/// the form used by the derive, since closure types can't be named in a field.
+pub type ArgumentFn<'buf, Argument> = Box<dyn Fn(usize, &'buf [u8]) -> Argument + 'buf>;
+
+/// An `Array` whose elements are each parsed with their own argument.  The
+/// argument for element `i` is produced by calling `F` with `i` and the buffer
+/// starting at that element, which is the base that self-relative offsets
+/// (like `idRangeOffset` in `cmap` format 4) are measured from.
+pub struct IndexedArray<'buf, Item, F = ArgumentFn<'buf, <Item as DeclarativeWithArgs<'buf>>::Argument>>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    buffer: &'buf [u8],
+    length: usize,
+    argument: F,
+    phantom: PhantomData<Item>,
+}
+
+impl<'buf, Item, F> fmt::Debug for IndexedArray<'buf, Item, F>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IndexedArray {{ length: {} }}", self.length)
+    }
+}
+
+impl<'buf, Item, F> DeclarativeWithArgs<'buf> for IndexedArray<'buf, Item, F>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    type Argument = (usize, F);
+    fn parse_with(
+        buffer: &'buf [u8],
+        arguments: (usize, F),
+    ) -> DeclResult<'buf, Self> {
+        let rest = skip_elements::<Item>(buffer, arguments.0)?;
+
+        Ok((
+            IndexedArray {
+                buffer: buffer,
+                length: arguments.0,
+                argument: arguments.1,
+                phantom: PhantomData,
+            },
+            rest,
+        ))
+    }
+}
+
+impl<'buf, Item, F> IndexedArray<'buf, Item, F>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Parses the element at `index`, or returns `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Result<Item, Error>> {
+        if self.length <= index {
+            return None;
+        }
+
+        let mut buffer = &self.buffer[index * Item::SIZE..];
+        let argument = (self.argument)(index, buffer);
+        Some(buffer.parse_with::<Item>(argument))
+    }
+
+    pub fn iter(&self) -> IndexedArrayIter<'buf, '_, Item, F> {
+        IndexedArrayIter {
+            array: self,
+            cursor: 0usize,
+        }
+    }
+
+    /// Parses every element, failing with the first error.
+    pub fn to_vec(&self) -> Result<Vec<Item>, Error> {
+        self.iter().collect_checked()
+    }
+}
+
+impl<'buf, Item, F> DynamicEncodingSize for IndexedArray<'buf, Item, F>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    fn size(&self) -> usize {
+        Item::SIZE * self.length
+    }
+}
+
+impl<'buf, Item, F> IntoOwned for IndexedArray<'buf, Item, F>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    type Owned = Vec<Item::Owned>;
+
+    fn into_owned(self) -> Result<Vec<Item::Owned>, Error> {
+        self.iter().map(|item| item?.into_owned()).collect_checked()
+    }
+}
+
+pub struct IndexedArrayIter<'buf, 'a, Item, F>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+    F: 'a,
+    'buf: 'a,
+{
+    array: &'a IndexedArray<'buf, Item, F>,
+    cursor: usize,
+}
+
+impl<'buf, 'a, Item, F> Iterator for IndexedArrayIter<'buf, 'a, Item, F>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    F: Fn(usize, &'buf [u8]) -> Item::Argument,
+{
+    type Item = Result<Item, Error>;
+    fn next(&mut self) -> Option<Result<Item, Error>> {
+        let dest = self.array.get(self.cursor);
+        if dest.is_some() {
+            self.cursor += 1;
+        }
+        dest
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.length - self.cursor;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Boxes `f` as an `ArgumentFn`.  Used by the derive for `#[element_argument]`.
+pub fn argument_fn<'buf, Argument, F>(f: F) -> ArgumentFn<'buf, Argument>
+where
+    F: Fn(usize, &'buf [u8]) -> Argument + 'buf,
+{
+    Box::new(f)
+}
+
+#[test]
+fn indexed_array_arguments() {
+    use primitives::BeU16;
+
+    let data = [0x00, 0x01, 0x00, 0x02, 0xFF];
+    let mut buffer = &data[..];
+    let array = buffer
+        .parse_with::<IndexedArray<BeU16, _>>((2, |index: usize, position: &[u8]| {
+            assert_eq!(position.len(), 5 - 2 * index);
+        }))
+        .unwrap();
+
+    assert_eq!(buffer, &[0xFF]);
+    assert_eq!(array.len(), 2);
+    assert_eq!(array.get(1), Some(Ok(BeU16::from(2))));
+    assert!(array.get(2).is_none());
+    assert_eq!(array.iter().count(), 2);
+}
+
+#[test]
+fn array2_indexing() {
+    let data = [1, 2, 3, 4, 5, 6, 0xFF];
+    let mut buffer = &data[..];
+    let matrix = buffer.parse_with::<Array2<u8>>((2, 3, ())).unwrap();
+
+    assert_eq!(buffer, &[0xFF]);
+    assert_eq!(matrix.get(1, 0), Some(Ok(4)));
+    assert_eq!(matrix.get(0, 3), None);
+    assert_eq!(matrix.get(2, 0), None);
+    assert_eq!(matrix.row(1).unwrap().get(2), Some(Ok(6)));
+    assert_eq!(matrix.iter_rows().count(), 2);
+
+    assert!((&data[..]).parse_with::<Array2<u8>>((3, 3, ())).is_err());
+    assert!((&data[..]).parse_with::<Array2<u8>>((usize::max_value(), 2, ())).is_err());
+}
+
+#[test]
+fn array_to_vec() {
+    use primitives::BeU16;
+    use terminated::UntilEnd;
+
+    let data = [0x00, 0x01, 0x00, 0x02, 0xFF];
+    let array = (&data[..]).parse_with::<Array<BeU16>>((2, ())).unwrap();
+    let values = array.to_vec().unwrap();
+    assert_eq!(values, vec![BeU16::from(1), BeU16::from(2)]);
+    assert_eq!(values.capacity(), 2);
+
+    let partial = (&data[..]).parse_with::<UntilEnd<BeU16>>(()).unwrap();
+    assert_eq!(partial.iter().collect_checked().err(), Some(Error::InsufficientBytes));
+}
+
+#[test]
+fn array_consumes_elements() {
+    use primitives::BeU16;
+
+    let data = [0x00, 0x01, 0x00, 0x02, 0xFF];
+    let mut buffer = &data[..];
+    let array = buffer.parse_with::<Array<BeU16>>((2, ())).unwrap();
+    assert_eq!(buffer, &[0xFF]);
+    assert_eq!(array.size(), 4);
+
+    assert_eq!((&data[..]).parse_with::<Array<BeU16>>((3, ())).err(), Some(Error::InsufficientBytes));
+    assert_eq!((&data[..]).parse_with::<Array<BeU16>>((usize::max_value(), ())).err(), Some(Error::InsufficientBytes));
+}
+
+#[test]
+fn array_elements_are_size_apart() {
+    use declarative::Declarative;
+
+    // Claims two bytes, but only parses the first.
+    #[derive(Debug, PartialEq)]
+    struct Short(u8);
+
+    impl<'buf> Declarative<'buf> for Short {
+        fn parse(buffer: &'buf [u8]) -> DeclResult<'buf, Self> {
+            let (value, rest) = u8::parse(buffer)?;
+            Ok((Short(value), rest))
+        }
+    }
+
+    impl StaticEncodingSize for Short {
+        const SIZE: usize = 2;
+    }
+
+    let data = [0x01, 0xEE, 0x02, 0xEE];
+    let array = (&data[..]).parse_with::<Array<Short>>((2, ())).unwrap();
+    assert_eq!(array.get(1), Some(Ok(Short(2))));
+    assert_eq!(array.iter().nth(1), Some(Ok(Short(2))));
+    assert_eq!(array.to_vec(), Ok(vec![Short(1), Short(2)]));
+}
@@ -0,0 +1,189 @@
+/// Implemented on the types a `bitfield!` field may have.  The bits are
+/// shifted down to start at bit 0, and are known to fit in the field, as
+/// `bitfield!` checks that no field is wider than its `BITS`.
+pub trait FromBits {
+    const BITS: usize;
+    fn from_bits(bits: u64) -> Self;
+}
+
+impl FromBits for bool {
+    const BITS: usize = 1;
+    fn from_bits(bits: u64) -> bool {
+        bits != 0
+    }
+}
+
+macro_rules! impl_from_bits {
+    ($($ty:ty),*) => (
+        $(
+            impl FromBits for $ty {
+                const BITS: usize = 8 * ::std::mem::size_of::<$ty>();
+                fn from_bits(bits: u64) -> $ty {
+                    bits as $ty
+                }
+            }
+        )*
+    )
+}
+
+impl_from_bits!(u8, u16, u32, u64);
+
+/// Declares a struct of sub-byte fields packed into a single integer, which
+/// implements `Declarative` and `StaticEncodingSize`.  Bits are numbered from
+/// the least significant, so `#[bit(0)]` is the low bit, and `#[bits(4..8)]`
+/// is the high nibble of the low byte.  Empty ranges, ranges past the end
+/// of the integer, and ranges wider than their field (more than one bit for
+/// a `bool`) fail to compile.
+///
+/// The integer is read as the given type, which may be any of the
+/// `primitives`; byte order wrappers name the integer after `as`.  Bits
+/// which are set in the optional `reserved` mask fail with
+/// `Error::InvalidValue`, naming the struct, with the reserved bits which
+/// were set as the value.
+///
+/// ```
+/// #[macro_use]
+/// extern crate decl;
+///
+/// use decl::primitives::LeU16;
+///
+/// bitfield! {
+///     #[derive(Debug)]
+///     pub struct Flags: LeU16 as u16 [reserved = 0xF000] {
+///         #[bit(0)] pub baseline_at_zero: bool,
+///         #[bits(4..8)] pub kind: u8,
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! bitfield {
+    (@range bit $bit:expr) => (($bit, $bit + 1));
+    (@range bits $low:tt .. $high:tt) => (($low, $high));
+
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident : $wire:ty $([reserved = $reserved:expr])* {
+            $(#[$kind:ident ( $($range:tt)* )] $field_vis:vis $field:ident : $field_ty:ty),* $(,)*
+        }
+    ) => (
+        bitfield! {
+            $(#[$attr])*
+            $vis struct $name : $wire as $wire $([reserved = $reserved])* {
+                $(#[$kind ( $($range)* )] $field_vis $field : $field_ty),*
+            }
+        }
+    );
+
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident : $wire:ty as $int:ty $([reserved = $reserved:expr])* {
+            $(#[$kind:ident ( $($range:tt)* )] $field_vis:vis $field:ident : $field_ty:ty),* $(,)*
+        }
+    ) => (
+        $(#[$attr])*
+        $vis struct $name {
+            $($field_vis $field: $field_ty,)*
+        }
+
+        // Every range must be non-empty, within the integer, and fit in its
+        // field.
+        $(
+        const _: () = {
+            let (low, high) = bitfield!(@range $kind $($range)*);
+            assert!(low < high, concat!("the bits of `", stringify!($field), "` are empty"));
+            assert!(high <= 8 * ::std::mem::size_of::<$int>(),
+                concat!("the bits of `", stringify!($field), "` are past the end of the integer"));
+            assert!(high - low <= <$field_ty as $crate::bitfield::FromBits>::BITS,
+                concat!("the bits of `", stringify!($field), "` don't fit in its type"));
+        };
+        )*
+
+        impl $name {
+            /// The bits which must be zero.
+            pub const RESERVED: $int = 0 $(| $reserved)*;
+
+            /// Unpacks the fields from `bits`, failing if reserved bits are set.
+            pub fn from_bits(bits: $int) -> ::std::result::Result<Self, $crate::error::Error> {
+                $(
+                if bits & ($reserved) != 0 {
+                    return Err($crate::error::Error::InvalidValue {
+                        field: stringify!($name),
+                        value: Some(::std::convert::From::from(bits & ($reserved))),
+                    });
+                }
+                )*
+
+                let bits = bits as u64;
+                Ok($name {
+                    $($field: {
+                        let (low, high) = bitfield!(@range $kind $($range)*);
+                        let mask = (!0u64) >> (64 - (high - low));
+                        $crate::bitfield::FromBits::from_bits((bits >> low) & mask)
+                    },)*
+                })
+            }
+        }
+
+        impl<'buf> $crate::declarative::Declarative<'buf> for $name {
+            fn parse(buffer: &'buf [u8]) -> $crate::declarative::DeclResult<'buf, Self> {
+                let (bits, rest) = <$wire as $crate::declarative::Declarative>::parse(buffer)?;
+                let bits: $int = ::std::convert::From::from(bits);
+                Ok(($name::from_bits(bits)?, rest))
+            }
+        }
+
+        impl $crate::declarative::StaticEncodingSize for $name {
+            const SIZE: usize = <$wire as $crate::declarative::StaticEncodingSize>::SIZE;
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use declarative::DeclRead;
+    use primitives::LeU16;
+    use error::Error;
+
+    bitfield! {
+        #[derive(Debug, PartialEq)]
+        struct Flags: LeU16 as u16 [reserved = 0xF000] {
+            #[bit(0)] low: bool,
+            #[bit(1)] next: bool,
+            #[bits(4..8)] nibble: u8,
+            #[bits(8..12)] high: u16,
+        }
+    }
+
+    bitfield! {
+        struct Byte: u8 {
+            #[bit(7)] top: bool,
+        }
+    }
+
+    bitfield! {
+        struct Whole: u64 {
+            #[bits(0..64)] all: u64,
+        }
+    }
+
+    bitfield! {
+        struct Word: LeU16 as u16 {
+            #[bits(0..16)] all: u16,
+        }
+    }
+
+    #[test]
+    fn bitfields() {
+        let flags = (&[0xA1, 0x03][..]).parse::<Flags>().unwrap();
+        assert_eq!(flags, Flags { low: true, next: false, nibble: 0xA, high: 3 });
+        assert_eq!(
+            (&[0x00, 0x10][..]).parse::<Flags>().err(),
+            Some(Error::InvalidValue { field: "Flags", value: Some(0x1000) })
+        );
+        assert!((&[0x80][..]).parse::<Byte>().unwrap().top);
+        assert_eq!(Whole::from_bits(!0).unwrap().all, !0);
+        assert_eq!(Whole::from_bits(0x0123_4567_89AB_CDEF).unwrap().all, 0x0123_4567_89AB_CDEF);
+        assert_eq!(Word::from_bits(0xFFFF).unwrap().all, 0xFFFF);
+    }
+}
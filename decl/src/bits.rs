@@ -0,0 +1,219 @@
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::fmt;
+
+use error::Error;
+
+/// The order bits are read from each byte, and assembled into values.
+pub trait BitOrder {
+    /// The `index`th bit read from `byte`.
+    fn bit(byte: u8, index: u32) -> bool;
+
+    /// Appends the `index`th bit read to `value`.
+    fn push(value: u64, bit: bool, index: u32) -> u64;
+}
+
+/// Bits are read from the most significant bit of each byte, and the first
+/// bit read is the most significant bit of a value.  Used by H.264 and most
+/// bitstreams.
+pub enum MsbFirst {}
+
+/// Bits are read from the least significant bit of each byte, and the first
+/// bit read is the least significant bit of a value.  Used by DEFLATE.
+pub enum LsbFirst {}
+
+impl BitOrder for MsbFirst {
+    fn bit(byte: u8, index: u32) -> bool {
+        byte & (0x80 >> index) != 0
+    }
+
+    fn push(value: u64, bit: bool, _: u32) -> u64 {
+        (value << 1) | bit as u64
+    }
+}
+
+impl BitOrder for LsbFirst {
+    fn bit(byte: u8, index: u32) -> bool {
+        byte & (0x01 << index) != 0
+    }
+
+    fn push(value: u64, bit: bool, index: u32) -> u64 {
+        value | (bit as u64) << index
+    }
+}
+
+/// A cursor over the bits of a buffer, for formats which aren't byte
+/// aligned.  Once the bit-level fields are read, `into_aligned` hands back
+/// the buffer from the next byte boundary for `DeclRead`.
+pub struct BitReader<'buf, O> {
+    buffer: &'buf [u8],
+    // The number of bits of `buffer[0]` already read, from 0 to 7.
+    consumed: u32,
+    phantom: PhantomData<O>,
+}
+
+impl<'buf, O> fmt::Debug for BitReader<'buf, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BitReader {{ remaining: {} }}", self.remaining())
+    }
+}
+
+impl<'buf, O> BitReader<'buf, O> {
+    /// The number of bits left to read.
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() * 8 - self.consumed as usize
+    }
+
+    pub fn is_aligned(&self) -> bool {
+        self.consumed == 0
+    }
+
+    /// Skips the rest of a partially read byte.
+    pub fn align(&mut self) {
+        if self.consumed != 0 {
+            self.buffer = &self.buffer[1..];
+            self.consumed = 0;
+        }
+    }
+
+    /// Skips to the next byte boundary, returning the rest of the buffer.
+    pub fn into_aligned(mut self) -> &'buf [u8] {
+        self.align();
+        self.buffer
+    }
+}
+
+impl<'buf, O> BitReader<'buf, O>
+where
+    O: BitOrder,
+{
+    pub fn new(buffer: &'buf [u8]) -> Self {
+        BitReader {
+            buffer: buffer,
+            consumed: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    // The caller has checked there is a bit remaining.
+    fn next_bit(&mut self) -> bool {
+        let bit = O::bit(self.buffer[0], self.consumed);
+        self.consumed += 1;
+        if self.consumed == 8 {
+            self.buffer = &self.buffer[1..];
+            self.consumed = 0;
+        }
+        bit
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        if self.remaining() < 1 {
+            return Err(Error::InsufficientBytes);
+        }
+
+        Ok(self.next_bit())
+    }
+
+    /// Reads an unsigned value of `count` bits, up to 64, and more is an
+    /// `Error::Overflow`.  Nothing is consumed if there are fewer than
+    /// `count` bits left.
+    pub fn read_bits(&mut self, count: u32) -> Result<u64, Error> {
+        if 64 < count {
+            return Err(Error::Overflow);
+        }
+        if self.remaining() < count as usize {
+            return Err(Error::InsufficientBytes);
+        }
+
+        let mut value = 0u64;
+        for index in 0..count {
+            let bit = self.next_bit();
+            value = O::push(value, bit, index);
+        }
+        Ok(value)
+    }
+
+    /// Reads a two's complement value of `count` bits, up to 64.
+    pub fn read_signed(&mut self, count: u32) -> Result<i64, Error> {
+        let value = self.read_bits(count)?;
+        match count {
+            0 => Ok(0),
+            64 => Ok(value as i64),
+            _ => {
+                let shift = 64 - count;
+                Ok(((value << shift) as i64) >> shift)
+            },
+        }
+    }
+
+    /// Reads a unary value: the number of bits read before the first bit
+    /// equal to `terminator`, which is consumed.
+    pub fn read_unary(&mut self, terminator: bool) -> Result<u32, Error> {
+        let mut count = 0u32;
+        loop {
+            if self.read_bool()? == terminator {
+                return Ok(count);
+            }
+            count += 1;
+        }
+    }
+
+    /// Reads an unsigned Exp-Golomb code, as `ue(v)` in H.264.  Codes over
+    /// 64 bits are an `Error::InvalidEncoding`.
+    pub fn read_exp_golomb(&mut self) -> Result<u64, Error> {
+        let zeros = self.read_unary(true)?;
+        if 63 < zeros {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let suffix = self.read_bits(zeros)?;
+        Ok((1u64 << zeros) - 1 + suffix)
+    }
+
+    /// Reads a signed Exp-Golomb code, as `se(v)` in H.264, which maps
+    /// 1, 2, 3, 4, ... to 1, -1, 2, -2, ...  Values outside of an `i64`
+    /// are an `Error::Overflow`.
+    pub fn read_signed_exp_golomb(&mut self) -> Result<i64, Error> {
+        let code = self.read_exp_golomb()?;
+        let magnitude = i64::try_from(code / 2 + code % 2).map_err(|_| Error::Overflow)?;
+        match code % 2 {
+            1 => Ok(magnitude),
+            _ => Ok(-magnitude),
+        }
+    }
+}
+
+#[test]
+fn msb_first_bits() {
+    // 101 00111 | 1 010 011 0
+    let data = [0b1010_0111, 0b1010_0110, 0xFF];
+    let mut bits = BitReader::<MsbFirst>::new(&data);
+
+    assert_eq!(bits.read_bits(3), Ok(0b101));
+    assert_eq!(bits.read_signed(5), Ok(0b00111));
+    assert_eq!(bits.read_exp_golomb(), Ok(0));
+    assert_eq!(bits.read_exp_golomb(), Ok(1));
+    assert_eq!(bits.read_signed_exp_golomb(), Ok(-1));
+    assert!(!bits.is_aligned());
+    assert_eq!(bits.read_bits(10), Err(Error::InsufficientBytes));
+    assert_eq!(bits.read_bits(65), Err(Error::Overflow));
+    assert_eq!(bits.into_aligned(), &[0xFF]);
+
+    // The largest code: 63 zeros, then a 64 bit value of 2^64 - 3.
+    let data = [0, 0, 0, 0, 0, 0, 0, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFC];
+    let mut bits = BitReader::<MsbFirst>::new(&data);
+    assert_eq!(bits.read_signed_exp_golomb(), Ok(i64::max_value()));
+}
+
+#[test]
+fn lsb_first_bits() {
+    // A DEFLATE header: BFINAL = 1, BTYPE = 01.
+    let data = [0b0000_0011, 0b1111_0000];
+    let mut bits = BitReader::<LsbFirst>::new(&data);
+
+    assert_eq!(bits.read_bool(), Ok(true));
+    assert_eq!(bits.read_bits(2), Ok(0b01));
+    assert_eq!(bits.read_bits(9), Ok(0));
+    assert_eq!(bits.read_signed(4), Ok(-1));
+    assert_eq!(bits.remaining(), 0);
+}
@@ -0,0 +1,93 @@
+use std::ops::Deref;
+
+use error::Error;
+use declarative::DeclResult;
+use declarative::DeclarativeWithArgs;
+use owned::IntoOwned;
+
+macro_rules! define_bounded {
+    ($($(#[$attr:meta])* $name:ident => $strict:expr),* $(,)*) => (
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct $name<Item>(Item);
+
+            impl<Item> $name<Item> {
+                pub fn into_inner(self) -> Item {
+                    self.0
+                }
+            }
+
+            impl<Item> Deref for $name<Item> {
+                type Target = Item;
+
+                fn deref(&self) -> &Item {
+                    &self.0
+                }
+            }
+
+            impl<Item> IntoOwned for $name<Item>
+            where
+                Item: IntoOwned,
+            {
+                type Owned = $name<Item::Owned>;
+
+                fn into_owned(self) -> Result<$name<Item::Owned>, Error> {
+                    self.0.into_owned().map($name)
+                }
+            }
+
+            impl<'buf, Item> DeclarativeWithArgs<'buf> for $name<Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+            {
+                type Argument = (usize, Item::Argument);
+
+                fn parse_with(
+                    buffer: &'buf [u8],
+                    (size, argument): Self::Argument,
+                ) -> DeclResult<'buf, Self> {
+                    if buffer.len() < size {
+                        return Err(Error::InsufficientBytes);
+                    }
+
+                    let (item, rest) = Item::parse_with(&buffer[..size], argument)?;
+                    if $strict && !rest.is_empty() {
+                        return Err(Error::LengthMismatch);
+                    }
+
+                    Ok(($name(item), &buffer[size..]))
+                }
+            }
+        )*
+    )
+}
+
+define_bounded!(
+    /// An item parsed from the next `size` bytes only, after which parsing
+    /// continues from the end of them however many the item used.
+    Bounded => false,
+    /// As `Bounded`, but bytes left unused by the item are an
+    /// `Error::LengthMismatch`.
+    Exact => true,
+);
+
+#[test]
+fn bounded_items() {
+    use declarative::DeclRead;
+    use array::Array;
+
+    let data = [0x00, 0x01, 0xEE, 0xEE, 0x02];
+    let mut buffer = &data[..];
+    let bounded = buffer.parse_with::<Bounded<u16>>((4, ())).unwrap();
+    assert_eq!(*bounded, 1);
+    assert_eq!(buffer, &[0x02]);
+
+    assert_eq!((&data[..]).parse_with::<Exact<u16>>((4, ())), Err(Error::LengthMismatch));
+    assert_eq!((&data[..]).parse_with::<Exact<u16>>((2, ())).map(Exact::into_inner), Ok(1));
+    assert_eq!((&data[..]).parse_with::<Bounded<u16>>((6, ())), Err(Error::InsufficientBytes));
+    // The item can't read past the bound.
+    assert_eq!((&data[..]).parse_with::<Bounded<u16>>((1, ())), Err(Error::InsufficientBytes));
+    let array = (&data[..]).parse_with::<Bounded<Array<u8>>>((2, (2, ()))).unwrap();
+    assert_eq!(array.len(), 2);
+}
@@ -77,6 +77,15 @@ pub trait DeclRead<'buf>: Sized {
     fn tag<T>(&mut self, tag: T) -> Result<&[u8], Error>
     where
         T: Tag;
+
+    /// Skips `count` bytes.
+    fn skip(&mut self, count: usize) -> Result<(), Error>;
+
+    /// Skips to the next multiple of `align` bytes from the start of `base`,
+    /// which the buffer must be a part of.  An `align` of zero, or a buffer
+    /// outside of `base`, is an `Error::InvalidEncoding`.  Named after
+    /// `#[pad_to]`, as slices already have an inherent `align_to`.
+    fn pad_to(&mut self, base: &'buf [u8], align: usize) -> Result<(), Error>;
 }
 
 impl<'buf> DeclRead<'buf> for &'buf [u8] {
@@ -104,6 +113,24 @@ impl<'buf> DeclRead<'buf> for &'buf [u8] {
     {
         Tag::validate(&tag, self)
     }
+
+    fn skip(&mut self, count: usize) -> Result<(), Error> {
+        if self.len() < count {
+            return Err(Error::InsufficientBytes);
+        }
+
+        *self = &self[count..];
+        Ok(())
+    }
+
+    fn pad_to(&mut self, base: &'buf [u8], align: usize) -> Result<(), Error> {
+        let position = match ::span::position(base, self) {
+            Some(position) if align != 0 => position,
+            _ => return Err(Error::InvalidEncoding),
+        };
+        let padding = (align - position % align) % align;
+        self.skip(padding)
+    }
 }
 
 pub trait Tag {
@@ -137,12 +164,22 @@ impl Tag for [u8] {
 }
 
 
+impl Tag for char {
+    fn validate<'b>(&self, buffer: &'b [u8]) -> Result<&'b [u8], Error> {
+        let mut bytes = [0u8; 4];
+        Tag::validate(self.encode_utf8(&mut bytes), buffer)
+    }
+}
+
 macro_rules! impl_tag_primitive {
     ($($ty:ty),*) => {
         $(
             impl Tag for $ty {
                 fn validate<'b>(&self, buffer: &'b [u8]) -> Result<&'b [u8], Error> {
-                    Self::parse(buffer).map(|(_, buffer)| buffer)
+                    match Self::parse(buffer)? {
+                        (value, buffer) if value == *self => Ok(buffer),
+                        _ => Err(Error::InvalidVersion),
+                    }
                 }
             }
         )*
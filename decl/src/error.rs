@@ -1,6 +1,81 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     InsufficientBytes,
     InvalidEncoding,
     InvalidVersion,
+    LengthMismatch,
+    Overflow,
+    /// A field failed a `range` or `must_be` check in the derive, or the
+    /// `reserved` bytes before it weren't zero.  Reserved bits set in a
+    /// `bitfield!` name the struct instead.  The value is given when it is
+    /// an integer.  `validate` functions may return it too.
+    InvalidValue {
+        field: &'static str,
+        value: Option<i128>,
+    },
+    /// An error along with where in the buffer it happened, added by
+    /// derived parsers and `Cursor`.  `Error::position` finds its offset.
+    At(Location, Box<Error>),
+}
+
+/// Where in a buffer an error happened.  As with `span::position`, this is
+/// the address of the bytes, which is only meaningful relative to the
+/// buffer they were parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location(usize);
+
+impl Location {
+    pub fn of(bytes: &[u8]) -> Location {
+        Location(bytes.as_ptr() as usize)
+    }
+
+    /// The offset of the location within `origin`, if it is a part of it.
+    pub fn position(&self, origin: &[u8]) -> Option<usize> {
+        let position = self.0.wrapping_sub(origin.as_ptr() as usize);
+        match position <= origin.len() {
+            true => Some(position),
+            false => None,
+        }
+    }
+}
+
+impl Error {
+    /// Locates the error at the start of `bytes`.  An error which already
+    /// has a location keeps it, so the innermost parser to fail wins.
+    pub fn at(self, bytes: &[u8]) -> Error {
+        match self {
+            Error::At(..) => self,
+            error => Error::At(Location::of(bytes), Box::new(error)),
+        }
+    }
+
+    /// The offset the error happened at within `origin`, if it has a
+    /// location and it is a part of `origin`.
+    pub fn position(&self, origin: &[u8]) -> Option<usize> {
+        match *self {
+            Error::At(location, _) => location.position(origin),
+            _ => None,
+        }
+    }
+
+    /// The error without its location.
+    pub fn inner(&self) -> &Error {
+        match *self {
+            Error::At(_, ref error) => error,
+            ref error => error,
+        }
+    }
+}
+
+#[test]
+fn located_errors() {
+    let data = [0u8; 8];
+    let error = Error::InvalidEncoding.at(&data[3..]);
+    assert_eq!(error.position(&data), Some(3));
+    assert_eq!(error.position(&data[4..]), None);
+    assert_eq!(error.inner(), &Error::InvalidEncoding);
+
+    let error = error.at(&data[5..]);
+    assert_eq!(error.position(&data), Some(3));
+    assert_eq!(Error::Overflow.position(&data), None);
 }
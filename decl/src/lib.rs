@@ -2,12 +2,25 @@
 
 extern crate byteorder;
 
-mod error;
+pub mod error;
 pub mod declarative;
 pub mod array;
 pub mod primitives;
 pub mod ignored;
 pub mod offset;
+pub mod parallel;
+pub mod prefixed;
+pub mod terminated;
+pub mod string;
+pub mod encoding;
+pub mod tag;
+#[macro_use]
+pub mod bitfield;
+pub mod bits;
+pub mod bounded;
+pub mod raw;
+pub mod span;
+pub mod owned;
 
 #[cfg(test)]
 mod tests {
@@ -7,9 +7,10 @@ use declarative::DeclResult;
 use declarative::Declarative;
 use declarative::DeclarativeWithArgs;
 use declarative::StaticEncodingSize;
+use owned::IntoOwned;
 
 macro_rules! define_offsets {
-    ($($ty:ident => $final:ident, $size:expr),* $(,)*) => (
+    ($($ty:ident => $final:ident, $nullable:ident, $size:expr),* $(,)*) => (
         $(
             pub struct $final<'buf, Item>
             where
@@ -17,9 +18,16 @@ macro_rules! define_offsets {
             {
                 buffer: &'buf [u8],
                 argument: Item::Argument,
+                null: bool,
                 phantom: PhantomData<Item>,
             }
 
+            /// As the offset of the same size, but an offset of zero means
+            /// the item is absent.
+            pub struct $nullable<'buf, Item>(Option<$final<'buf, Item>>)
+            where
+                Item: DeclarativeWithArgs<'buf>;
+
             impl<'buf, Item> fmt::Debug for $final<'buf, Item> 
             where
                 Item: DeclarativeWithArgs<'buf>,
@@ -50,6 +58,7 @@ macro_rules! define_offsets {
                         $final {
                             buffer: buffer,
                             argument: argument.1,
+                            null: offset == 0,
                             phantom: PhantomData,
                         },
                         rest,
@@ -57,6 +66,117 @@ macro_rules! define_offsets {
                 }
             }
 
+            impl<'buf, Item> $final<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+                Item::Argument: Clone,
+            {
+                /// Parses the item the offset points to.
+                pub fn get(&self) -> Result<Item, Error> {
+                    Item::parse_with(self.buffer, self.argument.clone()).map(|(item, _)| item)
+                }
+            }
+
+            impl<'buf, Item> $final<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+            {
+                /// Whether the offset is zero, which many formats use for an
+                /// absent item.
+                pub fn is_null(&self) -> bool {
+                    self.null
+                }
+            }
+
+            impl<'buf, Item> fmt::Debug for $nullable<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+            {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    match self.0 {
+                        Some(_) => write!(f, "Offset"),
+                        None => write!(f, "Null"),
+                    }
+                }
+            }
+
+            impl<'buf, Item> DeclarativeWithArgs<'buf> for $nullable<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+            {
+                type Argument = (&'buf [u8], Item::Argument);
+                fn parse_with(
+                    buffer: &'buf [u8],
+                    argument: Self::Argument,
+                ) -> DeclResult<'buf, Self> {
+                    let (offset, rest) = $final::<Item>::parse_with(buffer, argument)?;
+                    match offset.is_null() {
+                        true => Ok(($nullable(None), rest)),
+                        false => Ok(($nullable(Some(offset)), rest)),
+                    }
+                }
+            }
+
+            impl<'buf, Item> $nullable<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+                Item::Argument: Clone,
+            {
+                /// Parses the item the offset points to, or returns `None`
+                /// if the offset is null.
+                pub fn get(&self) -> Option<Result<Item, Error>> {
+                    self.0.as_ref().map($final::get)
+                }
+            }
+
+            impl<'buf, Item> $nullable<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+            {
+                pub fn is_null(&self) -> bool {
+                    self.0.is_none()
+                }
+
+                pub fn into_option(self) -> Option<$final<'buf, Item>> {
+                    self.0
+                }
+            }
+
+            impl<'buf, Item> IntoOwned for $nullable<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+                Item: IntoOwned,
+                Item::Argument: Clone,
+            {
+                type Owned = Option<Box<Item::Owned>>;
+
+                fn into_owned(self) -> Result<Option<Box<Item::Owned>>, Error> {
+                    self.0.into_owned()
+                }
+            }
+
+            impl<'buf, Item> StaticEncodingSize for $nullable<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>
+            {
+                const SIZE: usize = $size;
+            }
+
+            // A null offset is followed like any other, as zero is a valid
+            // offset in some formats.  The nullable offsets own as `None`.
+            impl<'buf, Item> IntoOwned for $final<'buf, Item>
+            where
+                Item: DeclarativeWithArgs<'buf>,
+                Item: IntoOwned,
+                Item::Argument: Clone,
+            {
+                type Owned = Box<Item::Owned>;
+
+                fn into_owned(self) -> Result<Box<Item::Owned>, Error> {
+                    self.get()?.into_owned().map(Box::new)
+                }
+            }
+
             impl<'buf, Item> StaticEncodingSize for $final<'buf, Item>
             where
                 Item: DeclarativeWithArgs<'buf>
@@ -68,8 +188,25 @@ macro_rules! define_offsets {
 }
 
 define_offsets!(
-    u8  => Offset8,  1,
-    u16 => Offset16, 2,
-    u32 => Offset32, 4,
-    u64 => Offset64, 8,
+    u8  => Offset8,  NullableOffset8,  1,
+    u16 => Offset16, NullableOffset16, 2,
+    u32 => Offset32, NullableOffset32, 4,
+    u64 => Offset64, NullableOffset64, 8,
 );
+
+#[test]
+fn nullable_offsets() {
+    let data = [0x00, 0x00, 0x00, 0x04, 0x00, 0x2A];
+    let mut buffer = &data[..];
+    let null = buffer.parse_with::<NullableOffset16<u16>>((&data[..], ())).unwrap();
+    let present = buffer.parse_with::<NullableOffset16<u16>>((&data[..], ())).unwrap();
+
+    assert!(null.is_null());
+    assert_eq!(null.get(), None);
+    assert_eq!(present.get(), Some(Ok(0x2A)));
+    assert_eq!(null.into_owned(), Ok(None));
+    assert_eq!(present.into_owned(), Ok(Some(Box::new(0x2A))));
+
+    let offset = (&data[..]).parse_with::<Offset16<u16>>((&data[..], ())).unwrap();
+    assert!(offset.is_null());
+}
@@ -0,0 +1,144 @@
+use error::Error;
+use array::CollectChecked;
+use primitives::BeU16;
+use primitives::BeI16;
+use primitives::BeU32;
+use primitives::BeI32;
+use primitives::BeU64;
+use primitives::BeI64;
+use primitives::LeU16;
+use primitives::LeI16;
+use primitives::LeU32;
+use primitives::LeI32;
+use primitives::LeU64;
+use primitives::LeI64;
+use primitives::NeU16;
+use primitives::NeI16;
+use primitives::NeU32;
+use primitives::NeI32;
+use primitives::NeU64;
+use primitives::NeI64;
+use primitives::Uleb128;
+use primitives::Sleb128;
+use primitives::Varint;
+use primitives::ZigZag;
+use primitives::Vlq;
+use primitives::UIntBase128;
+use primitives::UInt255U16;
+use tag::Tag4;
+
+/// Converts a parsed value into one which doesn't borrow the buffer, so that
+/// it may outlive it.  Lazy values are parsed in the process, so converting
+/// fails with the first error in them: arrays become `Vec`s, strings
+/// `String`s, offsets the `Box`ed item they point to (or `None` for a null
+/// nullable offset), and byte slices `Vec<u8>`s.
+pub trait IntoOwned {
+    type Owned: 'static;
+
+    fn into_owned(self) -> Result<Self::Owned, Error>;
+}
+
+macro_rules! impl_into_owned_copy {
+    ($($ty:ty),* $(,)*) => (
+        $(
+            impl IntoOwned for $ty {
+                type Owned = $ty;
+
+                fn into_owned(self) -> Result<$ty, Error> {
+                    Ok(self)
+                }
+            }
+        )*
+    )
+}
+
+impl_into_owned_copy!(
+    bool, u8, i8, u16, i16, u32, i32, u64, i64,
+    BeU16, BeI16, BeU32, BeI32, BeU64, BeI64,
+    LeU16, LeI16, LeU32, LeI32, LeU64, LeI64,
+    NeU16, NeI16, NeU32, NeI32, NeU64, NeI64,
+    Uleb128, Sleb128, Varint, ZigZag, Vlq, UIntBase128, UInt255U16,
+    Tag4,
+);
+
+impl<'buf> IntoOwned for &'buf [u8] {
+    type Owned = Vec<u8>;
+
+    fn into_owned(self) -> Result<Vec<u8>, Error> {
+        Ok(self.to_vec())
+    }
+}
+
+impl<T> IntoOwned for Option<T>
+where
+    T: IntoOwned,
+{
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Result<Option<T::Owned>, Error> {
+        match self {
+            Some(item) => item.into_owned().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T> IntoOwned for Vec<T>
+where
+    T: IntoOwned,
+{
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Result<Vec<T::Owned>, Error> {
+        self.into_iter().map(IntoOwned::into_owned).collect_checked()
+    }
+}
+
+impl<T> IntoOwned for Box<T>
+where
+    T: IntoOwned,
+{
+    type Owned = Box<T::Owned>;
+
+    fn into_owned(self) -> Result<Box<T::Owned>, Error> {
+        (*self).into_owned().map(Box::new)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($tup:ident),*) => (
+        impl<$($tup),*> IntoOwned for ($($tup),*)
+        where
+            $(
+            $tup: IntoOwned,
+            )*
+        {
+            type Owned = ($($tup::Owned),*);
+
+            #[allow(non_snake_case)]
+            fn into_owned(self) -> Result<Self::Owned, Error> {
+                let ($($tup),*) = self;
+                Ok(($($tup.into_owned()?),*))
+            }
+        }
+    )
+}
+
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+impl_tuple!(A, B, C, D, E, F, G, H, I);
+impl_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[test]
+fn owned_values() {
+    let bytes = [0x01, 0x02];
+    let owned = (Some(&bytes[..]), None::<u8>, BeU16::from(3)).into_owned();
+    assert_eq!(owned, Ok((Some(vec![0x01, 0x02]), None, BeU16::from(3))));
+}
@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use error::Error;
+use array::Array;
+use declarative::DeclarativeWithArgs;
+use declarative::StaticEncodingSize;
+
+/// A struct-of-arrays view over a tuple of equal length `Array`s.  Row `i`
+/// is the tuple of the `i`th element of every array, as with `cmap` format 4's
+/// `endCode`, `startCode`, `idDelta` and `idRangeOffset` arrays.
+pub struct Parallel<Arrays> {
+    arrays: Arrays,
+    length: usize,
+}
+
+impl<Arrays> fmt::Debug for Parallel<Arrays> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Parallel {{ length: {} }}", self.length)
+    }
+}
+
+/// Implemented on tuples of `Array`s which can be viewed as a `Parallel`.
+pub trait Columns {
+    type Row;
+
+    /// The common length of the arrays, or `Error::LengthMismatch` if
+    /// they differ.
+    fn length(&self) -> Result<usize, Error>;
+
+    /// Parses row `index` of every array.
+    fn row(&self, index: usize) -> Result<Self::Row, Error>;
+}
+
+impl<Arrays> Parallel<Arrays>
+where
+    Arrays: Columns,
+{
+    /// Zips `arrays` together, returning `Error::LengthMismatch` if their
+    /// lengths differ.
+    pub fn new(arrays: Arrays) -> Result<Self, Error> {
+        let length = arrays.length()?;
+        Ok(Parallel {
+            arrays: arrays,
+            length: length,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The underlying arrays, for access to a single column.
+    pub fn arrays(&self) -> &Arrays {
+        &self.arrays
+    }
+
+    /// Parses row `index`, or returns `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Result<Arrays::Row, Error>> {
+        if self.length <= index {
+            return None;
+        }
+
+        Some(self.arrays.row(index))
+    }
+
+    pub fn iter(&self) -> ParallelIter<Arrays> {
+        ParallelIter {
+            parallel: self,
+            cursor: 0usize,
+        }
+    }
+
+    /// Binary searches the rows with a comparator, in the manner of
+    /// `slice::binary_search_by`.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<Result<usize, usize>, Error>
+    where
+        F: FnMut(&Arrays::Row) -> Ordering,
+    {
+        let mut low = 0usize;
+        let mut high = self.length;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match f(&self.arrays.row(mid)?) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(Ok(mid)),
+            }
+        }
+
+        Ok(Err(low))
+    }
+
+    /// Binary searches the rows by a key, which is usually a single column,
+    /// e.g. `|row| row.0`.
+    pub fn binary_search_by_key<K, F>(&self, key: &K, mut f: F) -> Result<Result<usize, usize>, Error>
+    where
+        K: Ord,
+        F: FnMut(&Arrays::Row) -> K,
+    {
+        self.binary_search_by(|row| f(row).cmp(key))
+    }
+}
+
+pub struct ParallelIter<'a, Arrays: 'a> {
+    parallel: &'a Parallel<Arrays>,
+    cursor: usize,
+}
+
+impl<'a, Arrays> Iterator for ParallelIter<'a, Arrays>
+where
+    Arrays: Columns,
+{
+    type Item = Result<Arrays::Row, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let dest = self.parallel.get(self.cursor);
+        if dest.is_some() {
+            self.cursor += 1;
+        }
+        dest
+    }
+}
+
+macro_rules! impl_columns {
+    ($first:ident $first_idx:tt $(, $item:ident $idx:tt)*) => (
+        impl<'buf, $first $(, $item)*> Columns for (Array<'buf, $first> $(, Array<'buf, $item>)*)
+        where
+            $first: StaticEncodingSize + DeclarativeWithArgs<'buf>,
+            $first::Argument: Clone,
+            $(
+            $item: StaticEncodingSize + DeclarativeWithArgs<'buf>,
+            $item::Argument: Clone,
+            )*
+        {
+            type Row = ($first $(, $item)*);
+
+            fn length(&self) -> Result<usize, Error> {
+                let length = self.$first_idx.len();
+                $(
+                if self.$idx.len() != length {
+                    return Err(Error::LengthMismatch);
+                }
+                )*
+                Ok(length)
+            }
+
+            fn row(&self, index: usize) -> Result<Self::Row, Error> {
+                Ok((
+                    self.$first_idx.get(index).ok_or(Error::InsufficientBytes)??
+                    $(, self.$idx.get(index).ok_or(Error::InsufficientBytes)??)*
+                ))
+            }
+        }
+    )
+}
+
+impl_columns!(A 0, B 1);
+impl_columns!(A 0, B 1, C 2);
+impl_columns!(A 0, B 1, C 2, D 3);
+impl_columns!(A 0, B 1, C 2, D 3, E 4);
+impl_columns!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+#[test]
+fn parallel_arrays() {
+    use declarative::DeclRead;
+
+    let ends = [0x00, 0x10, 0x00, 0x20, 0xFF, 0xFF];
+    let starts = [0x00, 0x01, 0x00, 0x11, 0xFF, 0xFF];
+    let deltas = [0x01, 0x02, 0x03];
+
+    let parallel = Parallel::new((
+        (&ends[..]).parse_array::<u16>(3).unwrap(),
+        (&starts[..]).parse_array::<u16>(3).unwrap(),
+        (&deltas[..]).parse_array::<u8>(3).unwrap(),
+    )).unwrap();
+
+    assert_eq!(parallel.get(1), Some(Ok((0x20, 0x11, 0x02))));
+    assert_eq!(parallel.iter().count(), 3);
+    assert_eq!(parallel.binary_search_by_key(&0x18, |row| row.0), Ok(Err(1)));
+    assert_eq!(parallel.binary_search_by_key(&0xFFFF, |row| row.0), Ok(Ok(2)));
+
+    let mismatched = Parallel::new((
+        (&ends[..]).parse_array::<u16>(3).unwrap(),
+        (&deltas[..]).parse_array::<u8>(2).unwrap(),
+    ));
+    assert_eq!(mismatched.err(), Some(Error::LengthMismatch));
+}
@@ -0,0 +1,325 @@
+use std::convert::TryFrom;
+use std::ops::Deref;
+use std::fmt;
+
+use error::Error;
+use array::Array;
+use declarative::DeclRead;
+use declarative::DeclResult;
+use declarative::Declarative;
+use declarative::DeclarativeWithArgs;
+use declarative::StaticEncodingSize;
+use declarative::DynamicEncodingSize;
+use owned::IntoOwned;
+use terminated::UntilEnd;
+use terminated::UntilEndIter;
+use primitives::BeU16;
+use primitives::BeU32;
+use primitives::BeU64;
+use primitives::LeU16;
+use primitives::LeU32;
+use primitives::LeU64;
+use primitives::NeU16;
+use primitives::NeU32;
+use primitives::NeU64;
+use primitives::UInt255U16;
+use primitives::UIntBase128;
+use primitives::Uleb128;
+use primitives::Varint;
+use primitives::Vlq;
+
+/// Implemented on the unsigned integer types which may prefix a collection
+/// with its length.  A length which doesn't fit in a `usize` is an
+/// `Error::Overflow`.
+pub trait Count {
+    fn count(&self) -> Result<usize, Error>;
+}
+
+macro_rules! impl_count {
+    ($($ty:ident => $prim:ty),* $(,)*) => (
+        $(
+            impl Count for $ty {
+                fn count(&self) -> Result<usize, Error> {
+                    usize::try_from(<$prim>::from(*self)).map_err(|_| Error::Overflow)
+                }
+            }
+        )*
+    )
+}
+
+impl_count!(
+    u8 => u8,
+    u16 => u16,
+    u32 => u32,
+    u64 => u64,
+    BeU16 => u16,
+    BeU32 => u32,
+    BeU64 => u64,
+    LeU16 => u16,
+    LeU32 => u32,
+    LeU64 => u64,
+    NeU16 => u16,
+    NeU32 => u32,
+    NeU64 => u64,
+    Uleb128 => u64,
+    Varint => u64,
+    Vlq => u32,
+    UIntBase128 => u32,
+    UInt255U16 => u16,
+);
+
+/// An `Array` preceded by its number of elements, encoded as a `C`.
+/// For example a `Prefixed<'buf, u16, BeU32>` is a `u16` count followed by
+/// that many `BeU32`s.
+pub struct Prefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    array: Array<'buf, Item>,
+    count: C,
+}
+
+/// A collection preceded by its size in bytes, encoded as a `C`.  The
+/// elements run until that many bytes are exhausted, as an `UntilEnd` over
+/// them, so they needn't all be the same size.
+pub struct SizePrefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    elements: UntilEnd<'buf, Item>,
+    size: C,
+}
+
+impl<'buf, C, Item> fmt::Debug for Prefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Prefixed({:?})", self.array)
+    }
+}
+
+impl<'buf, C, Item> DeclarativeWithArgs<'buf> for Prefixed<'buf, C, Item>
+where
+    C: Declarative<'buf> + Count,
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Argument = Item::Argument;
+    fn parse_with(
+        mut buffer: &'buf [u8],
+        argument: Item::Argument,
+    ) -> DeclResult<'buf, Self> {
+        let count = buffer.parse::<C>()?;
+        let array = buffer.parse_with::<Array<Item>>((count.count()?, argument))?;
+
+        Ok((
+            Prefixed {
+                array: array,
+                count: count,
+            },
+            buffer,
+        ))
+    }
+}
+
+impl<'buf, C, Item> Prefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    pub fn into_array(self) -> Array<'buf, Item> {
+        self.array
+    }
+
+    /// The number of elements, as it was encoded.
+    pub fn count(&self) -> &C {
+        &self.count
+    }
+}
+
+impl<'buf, C, Item> Deref for Prefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Target = Array<'buf, Item>;
+    fn deref(&self) -> &Array<'buf, Item> {
+        &self.array
+    }
+}
+
+impl<'buf, C, Item> DynamicEncodingSize for Prefixed<'buf, C, Item>
+where
+    C: StaticEncodingSize,
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn size(&self) -> usize {
+        C::SIZE + self.array.size()
+    }
+}
+
+impl<'buf, C, Item> IntoOwned for Prefixed<'buf, C, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    Item::Argument: Clone,
+{
+    type Owned = Vec<Item::Owned>;
+
+    fn into_owned(self) -> Result<Vec<Item::Owned>, Error> {
+        self.array.into_owned()
+    }
+}
+
+impl<'buf, C, Item> IntoIterator for Prefixed<'buf, C, Item>
+where
+    Item: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    type IntoIter = <Array<'buf, Item> as IntoIterator>::IntoIter;
+    type Item = Result<Item, Error>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.array.into_iter()
+    }
+}
+
+impl<'buf, C, Item> fmt::Debug for SizePrefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SizePrefixed({:?})", self.elements)
+    }
+}
+
+impl<'buf, C, Item> DeclarativeWithArgs<'buf> for SizePrefixed<'buf, C, Item>
+where
+    C: Declarative<'buf> + Count,
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Argument = Item::Argument;
+    fn parse_with(
+        mut buffer: &'buf [u8],
+        argument: Item::Argument,
+    ) -> DeclResult<'buf, Self> {
+        let size = buffer.parse::<C>()?;
+        let length = size.count()?;
+        if buffer.len() < length {
+            return Err(Error::InsufficientBytes);
+        }
+
+        let mut elements = &buffer[..length];
+        let elements = elements.parse_with::<UntilEnd<Item>>(argument)?;
+
+        Ok((
+            SizePrefixed {
+                elements: elements,
+                size: size,
+            },
+            &buffer[length..],
+        ))
+    }
+}
+
+impl<'buf, C, Item> SizePrefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    pub fn into_until_end(self) -> UntilEnd<'buf, Item> {
+        self.elements
+    }
+
+    /// The size of the elements in bytes, as it was encoded.
+    pub fn size_prefix(&self) -> &C {
+        &self.size
+    }
+}
+
+impl<'buf, C, Item> Deref for SizePrefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Target = UntilEnd<'buf, Item>;
+    fn deref(&self) -> &UntilEnd<'buf, Item> {
+        &self.elements
+    }
+}
+
+impl<'buf, C, Item> DynamicEncodingSize for SizePrefixed<'buf, C, Item>
+where
+    C: StaticEncodingSize,
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn size(&self) -> usize {
+        C::SIZE + self.elements.size()
+    }
+}
+
+impl<'buf, C, Item> IntoOwned for SizePrefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    Item::Argument: Clone,
+{
+    type Owned = Vec<Item::Owned>;
+
+    fn into_owned(self) -> Result<Vec<Item::Owned>, Error> {
+        self.elements.into_owned()
+    }
+}
+
+impl<'buf, C, Item> IntoIterator for SizePrefixed<'buf, C, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    type IntoIter = UntilEndIter<'buf, Item>;
+    type Item = Result<Item, Error>;
+    fn into_iter(self) -> UntilEndIter<'buf, Item> {
+        self.elements.iter()
+    }
+}
+
+#[test]
+fn prefixed_arrays() {
+    let data = [0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0xFF];
+    let mut buffer = &data[..];
+    let array = buffer.parse_with::<Prefixed<u16, BeU32>>(()).unwrap();
+
+    assert_eq!(buffer, &[0xFF]);
+    assert_eq!(array.len(), 2);
+    assert_eq!(array.get(1), Some(Ok(BeU32::from(2))));
+    assert_eq!(*array.count(), 2);
+
+    let data = [0x04, 0x00, 0x01, 0x00, 0x02, 0xFF];
+    let mut buffer = &data[..];
+    let array = buffer.parse_with::<SizePrefixed<u8, u16>>(()).unwrap();
+    assert_eq!(buffer, &[0xFF]);
+    assert_eq!((array.size(), *array.size_prefix()), (5, 4));
+    assert_eq!(array.into_iter().collect::<Result<Vec<_>, _>>(), Ok(vec![1, 2]));
+
+    let data = [0x03, 0x00, 0x01, 0x00, 0x02];
+    let mut iter = (&data[..]).parse_with::<SizePrefixed<u8, u16>>(()).unwrap().into_iter();
+    assert_eq!(iter.next(), Some(Ok(1)));
+    assert_eq!(iter.next(), Some(Err(Error::InsufficientBytes)));
+
+    let data = [0x04, 0x02];
+    assert_eq!(
+        (&data[..]).parse_with::<SizePrefixed<u8, u16>>(()).err(),
+        Some(Error::InsufficientBytes)
+    );
+}
+
+#[test]
+fn size_prefixed_variable_elements() {
+    let data = [0x04, 0x01, 0x80, 0x01, 0x02, 0x03];
+    let mut buffer = &data[..];
+    let varints = buffer.parse_with::<SizePrefixed<u8, Uleb128>>(()).unwrap();
+    assert_eq!(buffer, &[0x03]);
+    assert_eq!(
+        varints.into_owned(),
+        Ok(vec![Uleb128::from(1), Uleb128::from(128), Uleb128::from(2)])
+    );
+}
@@ -60,6 +60,18 @@ macro_rules! define_wrappers {
                     $final(ty)
                 }
             }
+
+            impl From<$final> for $ty {
+                fn from(wrapper: $final) -> $ty {
+                    wrapper.0
+                }
+            }
+
+            impl From<$final> for i128 {
+                fn from(wrapper: $final) -> i128 {
+                    wrapper.0.into()
+                }
+            }
             
             impl<'buf> Declarative<'buf> for $final {
                 fn parse(buffer: &'buf [u8]) -> DeclResult<'buf, Self> {
@@ -102,6 +114,205 @@ define_wrappers!(
     NativeEndian::read_i64 => NeI64, i64, 8,
 );
 
+// Reads little-endian base 128 groups, as used by LEB128 and protobuf.  At
+// most `max_bytes` are read, and the final group may only hold the bits
+// which fit in `bits`.  A final group of zero after the first is padding,
+// which is as invalid as an encoding that is too long.
+fn read_leb128(buffer: &[u8], bits: u32) -> Result<(u64, usize), Error> {
+    let max_bytes = ((bits + 6) / 7) as usize;
+    let mut value = 0u64;
+    for (index, &byte) in buffer.iter().enumerate() {
+        if max_bytes <= index {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let shift = 7 * index as u32;
+        let group = (byte & 0x7F) as u64;
+        if bits < shift + 7 && group >> (bits - shift) != 0 {
+            return Err(Error::Overflow);
+        }
+
+        value |= group << shift;
+        if byte & 0x80 == 0 {
+            if index != 0 && group == 0 {
+                return Err(Error::InvalidEncoding);
+            }
+            return Ok((value, index + 1));
+        }
+    }
+
+    Err(Error::InsufficientBytes)
+}
+
+macro_rules! define_varints {
+    ($($final:ident, $ty:ty => |$buffer:ident| $read:expr),* $(,)*) => {
+        $(
+            impl From<$ty> for $final {
+                fn from(ty: $ty) -> $final {
+                    $final(ty)
+                }
+            }
+
+            impl From<$final> for $ty {
+                fn from(wrapper: $final) -> $ty {
+                    wrapper.0
+                }
+            }
+
+            impl From<$final> for i128 {
+                fn from(wrapper: $final) -> i128 {
+                    wrapper.0.into()
+                }
+            }
+
+            impl<'buf> Declarative<'buf> for $final {
+                fn parse($buffer: &'buf [u8]) -> DeclResult<'buf, Self> {
+                    let (dest, size): ($ty, usize) = $read;
+                    Ok(($final(dest), &$buffer[size..]))
+                }
+            }
+        )*
+    };
+}
+
+/// An unsigned LEB128 value, as used by WebAssembly and DWARF.  Encodings
+/// longer than 10 bytes or padded with zero groups are an
+/// `Error::InvalidEncoding`, and values over 64 bits are an `Error::Overflow`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Uleb128(u64);
+
+/// A signed LEB128 value, as used by WebAssembly and DWARF.  As with
+/// `Uleb128`, encodings longer than 10 bytes or padded with groups which
+/// only extend the sign are an `Error::InvalidEncoding`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Sleb128(i64);
+
+/// A protobuf varint.  This is the same encoding as `Uleb128`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Varint(u64);
+
+/// A protobuf `sint64`, a varint of the ZigZag encoding of the value, which
+/// maps 0, -1, 1, -2, ... to 0, 1, 2, 3, ...
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ZigZag(i64);
+
+/// A MIDI variable-length quantity: big-endian base 128 groups of at most
+/// four bytes, which may not have leading zeros.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Vlq(u32);
+
+/// WOFF2's `UIntBase128`: a big-endian base 128 `u32` of at most five bytes,
+/// which may not have leading zeros.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UIntBase128(u32);
+
+/// WOFF2's `255UInt16`: a single byte for small values, with escape codes
+/// for larger ones.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UInt255U16(u16);
+
+// As with `read_leb128`, a final group which only repeats the sign of the
+// group before it is padding.
+fn read_sleb128(buffer: &[u8]) -> Result<(i64, usize), Error> {
+    let mut value = 0i64;
+    for (index, &byte) in buffer.iter().enumerate() {
+        if 10 <= index {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let shift = 7 * index as u32;
+        let group = (byte & 0x7F) as i64;
+        if index == 9 && group != 0 && group != 0x7F {
+            return Err(Error::Overflow);
+        }
+
+        value |= group << shift;
+        if byte & 0x80 == 0 {
+            if index != 0 {
+                let negative = buffer[index - 1] & 0x40 != 0;
+                if (byte == 0x00 && !negative) || (byte == 0x7F && negative) {
+                    return Err(Error::InvalidEncoding);
+                }
+            }
+
+            // Sign extend from the last group read.
+            if shift + 7 < 64 && byte & 0x40 != 0 {
+                value |= !0i64 << (shift + 7);
+            }
+            return Ok((value, index + 1));
+        }
+    }
+
+    Err(Error::InsufficientBytes)
+}
+
+// As with `read_uint_base128`, a leading group of zero is padding.
+fn read_vlq(buffer: &[u8]) -> Result<(u32, usize), Error> {
+    let mut value = 0u32;
+    for (index, &byte) in buffer.iter().enumerate() {
+        if 4 <= index || (index == 0 && byte == 0x80) {
+            return Err(Error::InvalidEncoding);
+        }
+
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            return Ok((value, index + 1));
+        }
+    }
+
+    Err(Error::InsufficientBytes)
+}
+
+fn read_uint_base128(buffer: &[u8]) -> Result<(u32, usize), Error> {
+    let mut value = 0u32;
+    for (index, &byte) in buffer.iter().enumerate() {
+        if 5 <= index || (index == 0 && byte == 0x80) {
+            return Err(Error::InvalidEncoding);
+        }
+
+        if value & 0xFE00_0000 != 0 {
+            return Err(Error::Overflow);
+        }
+
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            return Ok((value, index + 1));
+        }
+    }
+
+    Err(Error::InsufficientBytes)
+}
+
+fn read_uint255_u16(buffer: &[u8]) -> Result<(u16, usize), Error> {
+    const WORD_CODE: u8 = 253;
+    const ONE_MORE_BYTE_CODE_2: u8 = 254;
+    const ONE_MORE_BYTE_CODE_1: u8 = 255;
+    const LOWEST_U_CODE: u16 = 253;
+
+    let (code, rest) = u8::parse(buffer)?;
+    match code {
+        WORD_CODE => u16::parse(rest).map(|(value, _)| (value, 3)),
+        ONE_MORE_BYTE_CODE_1 => u8::parse(rest)
+            .map(|(value, _)| (value as u16 + LOWEST_U_CODE, 2)),
+        ONE_MORE_BYTE_CODE_2 => u8::parse(rest)
+            .map(|(value, _)| (value as u16 + LOWEST_U_CODE * 2, 2)),
+        _ => Ok((code as u16, 1)),
+    }
+}
+
+define_varints!(
+    Uleb128, u64 => |buffer| read_leb128(buffer, 64)?,
+    Sleb128, i64 => |buffer| read_sleb128(buffer)?,
+    Varint, u64 => |buffer| read_leb128(buffer, 64)?,
+    ZigZag, i64 => |buffer| {
+        let (value, size) = read_leb128(buffer, 64)?;
+        (((value >> 1) as i64) ^ -((value & 1) as i64), size)
+    },
+    Vlq, u32 => |buffer| read_vlq(buffer)?,
+    UIntBase128, u32 => |buffer| read_uint_base128(buffer)?,
+    UInt255U16, u16 => |buffer| read_uint255_u16(buffer)?,
+);
+
 macro_rules! impl_tuple {
     ($($tup:ident),*) => (
             impl<'buf, $($tup),*> Declarative<'buf> for ($($tup),*)
@@ -120,6 +331,15 @@ macro_rules! impl_tuple {
                     ))
                 }
             }
+
+            impl<$($tup),*> StaticEncodingSize for ($($tup),*)
+            where
+                $(
+                $tup: StaticEncodingSize,
+                )*
+            {
+                const SIZE: usize = 0 $(+ $tup::SIZE)*;
+            }
     )
 }
 
@@ -134,3 +354,46 @@ impl_tuple!(A, B, C, D, E, F, G, H, I);
 impl_tuple!(A, B, C, D, E, F, G, H, I, J);
 impl_tuple!(A, B, C, D, E, F, G, H, I, J, K);
 impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[test]
+fn variable_length_integers() {
+    fn parse<'buf, T: Declarative<'buf>>(buffer: &'buf [u8]) -> Result<T, Error> {
+        T::parse(buffer).map(|(value, _)| value)
+    }
+
+    assert_eq!(parse::<Uleb128>(&[0xE5, 0x8E, 0x26]), Ok(Uleb128(624485)));
+    assert_eq!(parse::<Uleb128>(&[0xFF; 9][..]).err(), Some(Error::InsufficientBytes));
+    assert_eq!(parse::<Uleb128>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]), Ok(Uleb128(!0)));
+    assert_eq!(parse::<Uleb128>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02]), Err(Error::Overflow));
+    assert_eq!(parse::<Uleb128>(&[0x80; 11][..]).err(), Some(Error::InvalidEncoding));
+    assert_eq!(parse::<Uleb128>(&[0x80, 0x00]), Err(Error::InvalidEncoding));
+    assert_eq!(parse::<Uleb128>(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]), Err(Error::InvalidEncoding));
+    assert_eq!(parse::<Uleb128>(&[0x00]), Ok(Uleb128(0)));
+    assert_eq!(parse::<Varint>(&[0xFF, 0x00]), Err(Error::InvalidEncoding));
+
+    assert_eq!(parse::<Sleb128>(&[0xC0, 0xBB, 0x78]), Ok(Sleb128(-123456)));
+    assert_eq!(parse::<Sleb128>(&[0x3F]), Ok(Sleb128(63)));
+    assert_eq!(parse::<Sleb128>(&[0xC0, 0x00]), Ok(Sleb128(64)));
+    assert_eq!(parse::<Sleb128>(&[0x80, 0x7F]), Ok(Sleb128(-128)));
+    assert_eq!(parse::<Sleb128>(&[0x80, 0x00]), Err(Error::InvalidEncoding));
+    assert_eq!(parse::<Sleb128>(&[0xFF, 0x7F]), Err(Error::InvalidEncoding));
+    assert_eq!(parse::<Sleb128>(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F]), Ok(Sleb128(i64::min_value())));
+    assert_eq!(parse::<Sleb128>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]), Ok(Sleb128(i64::max_value())));
+    assert_eq!(parse::<ZigZag>(&[0x03]), Ok(ZigZag(-2)));
+    assert_eq!(parse::<ZigZag>(&[0x04]), Ok(ZigZag(2)));
+
+    assert_eq!(parse::<Vlq>(&[0x81, 0x80, 0x00]), Ok(Vlq(0x4000)));
+    assert_eq!(parse::<Vlq>(&[0xFF, 0xFF, 0xFF, 0x7F]), Ok(Vlq(0x0FFF_FFFF)));
+    assert_eq!(parse::<Vlq>(&[0x80, 0x80, 0x80, 0x80, 0x00]), Err(Error::InvalidEncoding));
+    assert_eq!(parse::<Vlq>(&[0x80, 0x01]), Err(Error::InvalidEncoding));
+    assert_eq!(parse::<Vlq>(&[0x00]), Ok(Vlq(0)));
+
+    assert_eq!(parse::<UIntBase128>(&[0x8F, 0xFF, 0xFF, 0xFF, 0x7F]), Ok(UIntBase128(!0)));
+    assert_eq!(parse::<UIntBase128>(&[0x90, 0x80, 0x80, 0x80, 0x00]), Err(Error::Overflow));
+    assert_eq!(parse::<UIntBase128>(&[0x80, 0x01]), Err(Error::InvalidEncoding));
+
+    assert_eq!(parse::<UInt255U16>(&[0xFD, 0x01, 0x00]), Ok(UInt255U16(256)));
+    assert_eq!(parse::<UInt255U16>(&[0xFF, 0x00]), Ok(UInt255U16(253)));
+    assert_eq!(parse::<UInt255U16>(&[0xFE, 0x00]), Ok(UInt255U16(506)));
+    assert_eq!(parse::<UInt255U16>(&[0x0A]), Ok(UInt255U16(10)));
+}
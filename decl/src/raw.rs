@@ -0,0 +1,92 @@
+use std::ops::Deref;
+
+use error::Error;
+use declarative::DeclResult;
+use declarative::DeclarativeWithArgs;
+use declarative::DynamicEncodingSize;
+use owned::IntoOwned;
+
+/// The next `length` bytes, unparsed.
+impl<'buf> DeclarativeWithArgs<'buf> for &'buf [u8] {
+    type Argument = usize;
+
+    fn parse_with(buffer: &'buf [u8], length: usize) -> DeclResult<'buf, Self> {
+        if buffer.len() < length {
+            return Err(Error::InsufficientBytes);
+        }
+
+        Ok(buffer.split_at(length))
+    }
+}
+
+/// An item along with the bytes it was parsed from, for hashing or passing
+/// through unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Raw<'buf, Item> {
+    item: Item,
+    bytes: &'buf [u8],
+}
+
+impl<'buf, Item> Raw<'buf, Item> {
+    pub fn bytes(&self) -> &'buf [u8] {
+        self.bytes
+    }
+
+    pub fn into_inner(self) -> Item {
+        self.item
+    }
+}
+
+impl<'buf, Item> Deref for Raw<'buf, Item> {
+    type Target = Item;
+
+    fn deref(&self) -> &Item {
+        &self.item
+    }
+}
+
+impl<'buf, Item> DeclarativeWithArgs<'buf> for Raw<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Argument = Item::Argument;
+
+    fn parse_with(buffer: &'buf [u8], argument: Item::Argument) -> DeclResult<'buf, Self> {
+        let (item, rest) = Item::parse_with(buffer, argument)?;
+        let bytes = &buffer[..buffer.len() - rest.len()];
+        Ok((Raw { item: item, bytes: bytes }, rest))
+    }
+}
+
+impl<'buf, Item> DynamicEncodingSize for Raw<'buf, Item> {
+    fn size(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+/// Owned as the owned item, and a copy of its bytes.
+impl<'buf, Item> IntoOwned for Raw<'buf, Item>
+where
+    Item: IntoOwned,
+{
+    type Owned = (Item::Owned, Vec<u8>);
+
+    fn into_owned(self) -> Result<(Item::Owned, Vec<u8>), Error> {
+        Ok((self.item.into_owned()?, self.bytes.to_vec()))
+    }
+}
+
+#[test]
+fn raw_bytes() {
+    use declarative::DeclRead;
+
+    let data = [0x00, 0x01, 0x02, 0x03];
+    let mut buffer = &data[..];
+    assert_eq!(buffer.parse_with::<&[u8]>(3), Ok(&data[..3]));
+    assert_eq!(buffer.parse_with::<&[u8]>(2), Err(Error::InsufficientBytes));
+
+    let mut buffer = &data[..];
+    let raw = buffer.parse_with::<Raw<u16>>(()).unwrap();
+    assert_eq!((*raw, raw.bytes(), raw.size()), (1, &data[..2], 2));
+    assert_eq!(buffer, &data[2..]);
+}
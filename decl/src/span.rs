@@ -0,0 +1,202 @@
+use std::ops::Range;
+
+use error::Error;
+use declarative::DeclRead;
+use declarative::Declarative;
+use declarative::DeclarativeWithArgs;
+use declarative::Tag;
+use owned::IntoOwned;
+
+/// The position of `bytes` within `origin`, if they are a part of it.  Every
+/// buffer handed out while parsing is a part of the original, so positions
+/// can be recovered without being tracked.
+pub fn position(origin: &[u8], bytes: &[u8]) -> Option<usize> {
+    let start = origin.as_ptr() as usize;
+    let position = (bytes.as_ptr() as usize).wrapping_sub(start);
+    match position <= origin.len() && bytes.len() <= origin.len() - position {
+        true => Some(position),
+        false => None,
+    }
+}
+
+/// The range of `bytes` within `origin`, if they are a part of it.
+pub fn span(origin: &[u8], bytes: &[u8]) -> Option<Range<usize>> {
+    position(origin, bytes).map(|start| start..start + bytes.len())
+}
+
+/// An item along with the range of the original buffer it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub item: T,
+    pub span: Range<usize>,
+}
+
+impl<T> IntoOwned for Spanned<T>
+where
+    T: IntoOwned,
+{
+    type Owned = Spanned<T::Owned>;
+
+    fn into_owned(self) -> Result<Spanned<T::Owned>, Error> {
+        Ok(Spanned {
+            item: self.item.into_owned()?,
+            span: self.span,
+        })
+    }
+}
+
+/// A buffer which knows its position within the original, for use in
+/// place of a bare `&[u8]` when reading values one by one.  Errors are
+/// located where the read which failed started, unless a derived parser
+/// located them within the value, and `error_position` finds their offset
+/// within the original.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor<'buf> {
+    origin: &'buf [u8],
+    buffer: &'buf [u8],
+}
+
+impl<'buf> Cursor<'buf> {
+    pub fn new(origin: &'buf [u8]) -> Self {
+        Cursor {
+            origin: origin,
+            buffer: origin,
+        }
+    }
+
+    pub fn origin(&self) -> &'buf [u8] {
+        self.origin
+    }
+
+    /// The bytes not yet parsed.
+    pub fn remaining(&self) -> &'buf [u8] {
+        self.buffer
+    }
+
+    pub fn position(&self) -> usize {
+        self.origin.len() - self.buffer.len()
+    }
+
+    /// Moves to `position` in the original buffer.
+    pub fn seek(&mut self, position: usize) -> Result<(), Error> {
+        if self.origin.len() < position {
+            return Err(Error::InsufficientBytes);
+        }
+
+        self.buffer = &self.origin[position..];
+        Ok(())
+    }
+
+    /// The offset within the original buffer that `error` happened at.
+    pub fn error_position(&self, error: &Error) -> Option<usize> {
+        error.position(self.origin)
+    }
+
+    /// The range of `bytes` within the original buffer.
+    pub fn span_of(&self, bytes: &[u8]) -> Option<Range<usize>> {
+        span(self.origin, bytes)
+    }
+
+    pub fn parse_spanned<T>(&mut self) -> Result<Spanned<T>, Error>
+    where
+        T: Declarative<'buf>,
+    {
+        self.parse_spanned_with(())
+    }
+
+    pub fn parse_spanned_with<T>(&mut self, argument: T::Argument) -> Result<Spanned<T>, Error>
+    where
+        T: DeclarativeWithArgs<'buf>,
+    {
+        let start = self.position();
+        let item = self.parse_with(argument)?;
+        Ok(Spanned {
+            item: item,
+            span: start..self.position(),
+        })
+    }
+}
+
+impl<'buf> DeclRead<'buf> for Cursor<'buf> {
+    fn parse<T>(&mut self) -> Result<T, Error>
+    where
+        T: Declarative<'buf>,
+    {
+        let buffer = self.buffer;
+        self.buffer.parse().map_err(|error: Error| error.at(buffer))
+    }
+
+    fn parse_with<T>(&mut self, argument: T::Argument) -> Result<T, Error>
+    where
+        T: DeclarativeWithArgs<'buf>,
+    {
+        let buffer = self.buffer;
+        self.buffer.parse_with(argument).map_err(|error: Error| error.at(buffer))
+    }
+
+    fn tag<T>(&mut self, tag: T) -> Result<&[u8], Error>
+    where
+        T: Tag,
+    {
+        let buffer = self.buffer;
+        self.buffer.tag(tag).map_err(|error| error.at(buffer))
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), Error> {
+        let buffer = self.buffer;
+        self.buffer.skip(count).map_err(|error| error.at(buffer))
+    }
+
+    fn pad_to(&mut self, base: &'buf [u8], align: usize) -> Result<(), Error> {
+        let buffer = self.buffer;
+        self.buffer.pad_to(base, align).map_err(|error| error.at(buffer))
+    }
+}
+
+/// The bytes each field of a derived struct was parsed from, recorded in a
+/// field marked `#[spans]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spans<'buf>(Vec<(&'static str, &'buf [u8])>);
+
+impl<'buf> Spans<'buf> {
+    pub fn new(fields: Vec<(&'static str, &'buf [u8])>) -> Self {
+        Spans(fields)
+    }
+
+    /// The bytes of the field named `field`, unless it was absent.
+    pub fn get(&self, field: &str) -> Option<&'buf [u8]> {
+        self.0.iter().find(|&&(name, _)| name == field).map(|&(_, bytes)| bytes)
+    }
+
+    /// The range of the field named `field` within `origin`.
+    pub fn span(&self, field: &str, origin: &[u8]) -> Option<Range<usize>> {
+        self.get(field).and_then(|bytes| span(origin, bytes))
+    }
+
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (&'static str, &'buf [u8])> + 'a {
+        self.0.iter().cloned()
+    }
+}
+
+#[test]
+fn cursor_spans() {
+    let data = [0x00, 0x01, 0x00, 0x02, 0x03, 0x04];
+    let mut cursor = Cursor::new(&data);
+
+    assert_eq!(cursor.parse::<u16>(), Ok(1));
+    let spanned = cursor.parse_spanned::<u16>().unwrap();
+    assert_eq!(spanned, Spanned { item: 2, span: 2..4 });
+
+    let array = cursor.parse_array::<u8>(2).unwrap();
+    assert_eq!((array.len(), cursor.position()), (2, 6));
+    assert_eq!(cursor.seek(7), Err(Error::InsufficientBytes));
+    let error = cursor.parse::<u32>().unwrap_err();
+    assert_eq!((cursor.error_position(&error), error.inner()), (Some(6), &Error::InsufficientBytes));
+    assert_eq!(cursor.position(), 6);
+    cursor.seek(1).unwrap();
+    assert_eq!(cursor.remaining(), &data[1..]);
+
+    assert_eq!(position(&data, &data[2..4]), Some(2));
+    assert_eq!(position(&data[1..], &data[..2]), None);
+    assert_eq!(span(&data[..4], &data[2..]), None);
+}
@@ -0,0 +1,384 @@
+use std::char::DecodeUtf16;
+use std::marker::PhantomData;
+use std::str;
+use std::fmt;
+
+use byteorder::{BE, LE, ByteOrder};
+
+use error::Error;
+use declarative::DeclRead;
+use declarative::DeclResult;
+use declarative::Declarative;
+use declarative::DeclarativeWithArgs;
+use owned::IntoOwned;
+use prefixed::Count;
+use encoding::Encoding;
+
+fn to_str(bytes: &[u8]) -> Result<&str, Error> {
+    str::from_utf8(bytes).map_err(|_| Error::InvalidEncoding)
+}
+
+/// A NUL terminated UTF-8 string.  The terminator is consumed, but isn't
+/// part of the string.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CStr<'buf>(&'buf str);
+
+/// A UTF-8 string prefixed by its length in bytes, encoded as a `C`.
+pub struct PascalStr<'buf, C = u8> {
+    string: &'buf str,
+    phantom: PhantomData<C>,
+}
+
+/// A UTF-8 string in a fixed-width field, padded with NULs.  The argument is
+/// the width of the field; the string ends at the first NUL, if any.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PaddedStr<'buf>(&'buf str);
+
+macro_rules! impl_str {
+    ($($final:ident),* $(,)*) => (
+        $(
+            impl<'buf> $final<'buf> {
+                pub fn as_str(&self) -> &'buf str {
+                    self.0
+                }
+
+                pub fn as_bytes(&self) -> &'buf [u8] {
+                    self.0.as_bytes()
+                }
+            }
+
+            impl<'buf> IntoOwned for $final<'buf> {
+                type Owned = String;
+
+                fn into_owned(self) -> Result<String, Error> {
+                    Ok(self.0.to_owned())
+                }
+            }
+
+            impl<'buf> fmt::Debug for $final<'buf> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::Debug::fmt(self.0, f)
+                }
+            }
+
+            impl<'buf> fmt::Display for $final<'buf> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::Display::fmt(self.0, f)
+                }
+            }
+        )*
+    )
+}
+
+impl_str!(CStr, PaddedStr);
+
+impl<'buf> Declarative<'buf> for CStr<'buf> {
+    fn parse(buffer: &'buf [u8]) -> DeclResult<'buf, Self> {
+        let end = match buffer.iter().position(|&byte| byte == 0) {
+            Some(end) => end,
+            None => return Err(Error::InsufficientBytes),
+        };
+
+        Ok((CStr(to_str(&buffer[..end])?), &buffer[end + 1..]))
+    }
+}
+
+impl<'buf> DeclarativeWithArgs<'buf> for PaddedStr<'buf> {
+    type Argument = usize;
+    fn parse_with(buffer: &'buf [u8], width: usize) -> DeclResult<'buf, Self> {
+        if buffer.len() < width {
+            return Err(Error::InsufficientBytes);
+        }
+
+        let field = &buffer[..width];
+        let end = field.iter().position(|&byte| byte == 0).unwrap_or(width);
+        Ok((PaddedStr(to_str(&field[..end])?), &buffer[width..]))
+    }
+}
+
+impl<'buf, C> PascalStr<'buf, C> {
+    pub fn as_str(&self) -> &'buf str {
+        self.string
+    }
+
+    pub fn as_bytes(&self) -> &'buf [u8] {
+        self.string.as_bytes()
+    }
+}
+
+impl<'buf, C> fmt::Debug for PascalStr<'buf, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.string, f)
+    }
+}
+
+impl<'buf, C> fmt::Display for PascalStr<'buf, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.string, f)
+    }
+}
+
+impl<'buf, C> IntoOwned for PascalStr<'buf, C> {
+    type Owned = String;
+
+    fn into_owned(self) -> Result<String, Error> {
+        Ok(self.string.to_owned())
+    }
+}
+
+impl<'buf, C> Declarative<'buf> for PascalStr<'buf, C>
+where
+    C: Declarative<'buf> + Count,
+{
+    fn parse(mut buffer: &'buf [u8]) -> DeclResult<'buf, Self> {
+        let length = buffer.parse::<C>()?.count()?;
+        if buffer.len() < length {
+            return Err(Error::InsufficientBytes);
+        }
+
+        let string = PascalStr {
+            string: to_str(&buffer[..length])?,
+            phantom: PhantomData,
+        };
+        Ok((string, &buffer[length..]))
+    }
+}
+
+/// A UTF-16 string in the byte order `E`, whose argument is its length in
+/// bytes.  The string is decoded lazily, so unpaired surrogates are only
+/// reported by `chars` and `to_string`.
+pub struct Utf16<'buf, E> {
+    buffer: &'buf [u8],
+    phantom: PhantomData<E>,
+}
+
+pub type Utf16Be<'buf> = Utf16<'buf, BE>;
+pub type Utf16Le<'buf> = Utf16<'buf, LE>;
+
+impl<'buf, E> fmt::Debug for Utf16<'buf, E>
+where
+    E: ByteOrder,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_string() {
+            Ok(string) => fmt::Debug::fmt(&string, f),
+            Err(_) => write!(f, "Utf16 {{ size: {} }}", self.buffer.len()),
+        }
+    }
+}
+
+impl<'buf, E> DeclarativeWithArgs<'buf> for Utf16<'buf, E>
+where
+    E: ByteOrder,
+{
+    type Argument = usize;
+    fn parse_with(buffer: &'buf [u8], size: usize) -> DeclResult<'buf, Self> {
+        if buffer.len() < size {
+            return Err(Error::InsufficientBytes);
+        }
+
+        if size % 2 != 0 {
+            return Err(Error::InvalidEncoding);
+        }
+
+        let string = Utf16 {
+            buffer: &buffer[..size],
+            phantom: PhantomData,
+        };
+        Ok((string, &buffer[size..]))
+    }
+}
+
+impl<'buf, E> Utf16<'buf, E>
+where
+    E: ByteOrder,
+{
+    pub fn as_bytes(&self) -> &'buf [u8] {
+        self.buffer
+    }
+
+    /// The UTF-16 code units of the string.
+    pub fn units(&self) -> Utf16Units<'buf, E> {
+        Utf16Units {
+            buffer: self.buffer,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn chars(&self) -> Utf16Chars<'buf, E> {
+        Utf16Chars(::std::char::decode_utf16(self.units()))
+    }
+
+    pub fn to_string(&self) -> Result<String, Error> {
+        self.chars().collect()
+    }
+}
+
+impl<'buf, E> IntoOwned for Utf16<'buf, E>
+where
+    E: ByteOrder,
+{
+    type Owned = String;
+
+    fn into_owned(self) -> Result<String, Error> {
+        self.to_string()
+    }
+}
+
+pub struct Utf16Units<'buf, E> {
+    buffer: &'buf [u8],
+    phantom: PhantomData<E>,
+}
+
+impl<'buf, E> Iterator for Utf16Units<'buf, E>
+where
+    E: ByteOrder,
+{
+    type Item = u16;
+    fn next(&mut self) -> Option<u16> {
+        if self.buffer.len() < 2 {
+            return None;
+        }
+
+        let unit = E::read_u16(self.buffer);
+        self.buffer = &self.buffer[2..];
+        Some(unit)
+    }
+}
+
+pub struct Utf16Chars<'buf, E>(DecodeUtf16<Utf16Units<'buf, E>>)
+where
+    E: ByteOrder;
+
+impl<'buf, E> Iterator for Utf16Chars<'buf, E>
+where
+    E: ByteOrder,
+{
+    type Item = Result<char, Error>;
+    fn next(&mut self) -> Option<Result<char, Error>> {
+        self.0.next().map(|ch| ch.map_err(|_| Error::InvalidEncoding))
+    }
+}
+
+/// A string in a single-byte legacy encoding, such as a Mac Roman `name`
+/// table record.  The argument is the length in bytes and the encoding.
+/// Characters are decoded as they are read, and nothing is allocated until
+/// `to_string` (through `Display`) is called.
+#[derive(Copy, Clone)]
+pub struct LegacyStr<'buf> {
+    buffer: &'buf [u8],
+    encoding: Encoding,
+}
+
+impl<'buf> DeclarativeWithArgs<'buf> for LegacyStr<'buf> {
+    type Argument = (usize, Encoding);
+    fn parse_with(buffer: &'buf [u8], argument: (usize, Encoding)) -> DeclResult<'buf, Self> {
+        if buffer.len() < argument.0 {
+            return Err(Error::InsufficientBytes);
+        }
+
+        let string = LegacyStr {
+            buffer: &buffer[..argument.0],
+            encoding: argument.1,
+        };
+        Ok((string, &buffer[argument.0..]))
+    }
+}
+
+impl<'buf> LegacyStr<'buf> {
+    pub fn as_bytes(&self) -> &'buf [u8] {
+        self.buffer
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn chars(&self) -> LegacyChars<'buf> {
+        LegacyChars {
+            bytes: self.buffer.iter(),
+            encoding: self.encoding,
+        }
+    }
+}
+
+impl<'buf> fmt::Debug for LegacyStr<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl<'buf> fmt::Display for LegacyStr<'buf> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use std::fmt::Write;
+
+        for ch in self.chars() {
+            f.write_char(ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'buf> IntoOwned for LegacyStr<'buf> {
+    type Owned = String;
+
+    fn into_owned(self) -> Result<String, Error> {
+        Ok(self.chars().collect())
+    }
+}
+
+pub struct LegacyChars<'buf> {
+    bytes: ::std::slice::Iter<'buf, u8>,
+    encoding: Encoding,
+}
+
+impl<'buf> Iterator for LegacyChars<'buf> {
+    type Item = char;
+    fn next(&mut self) -> Option<char> {
+        let encoding = self.encoding;
+        self.bytes.next().map(|&byte| encoding.decode(byte))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bytes.size_hint()
+    }
+}
+
+#[test]
+fn string_types() {
+    use primitives::BeU16;
+
+    let mut buffer = &b"cmap\0rest"[..];
+    assert_eq!(buffer.parse::<CStr>().unwrap().as_str(), "cmap");
+    assert_eq!(buffer, b"rest");
+    assert_eq!((&b"cmap"[..]).parse::<CStr>().err(), Some(Error::InsufficientBytes));
+    assert_eq!((&b"\xFF\0"[..]).parse::<CStr>().err(), Some(Error::InvalidEncoding));
+
+    let mut buffer = &b"\x00\x03abcd"[..];
+    assert_eq!(buffer.parse::<PascalStr<BeU16>>().unwrap().as_str(), "abc");
+    assert_eq!(buffer, b"d");
+
+    let mut buffer = &b"ab\0\0\0d"[..];
+    assert_eq!(buffer.parse_with::<PaddedStr>(5).unwrap().as_str(), "ab");
+    assert_eq!(buffer, b"d");
+}
+
+#[test]
+fn utf16_strings() {
+    let data = [0x00, 0x41, 0xD8, 0x3D, 0xDE, 0x00, 0xD8, 0x00];
+    let string = (&data[..]).parse_with::<Utf16Be>(6).unwrap();
+    assert_eq!(string.to_string(), Ok(String::from("A\u{1F600}")));
+
+    let string = (&data[..]).parse_with::<Utf16Be>(8).unwrap();
+    assert_eq!(string.to_string(), Err(Error::InvalidEncoding));
+    assert_eq!((&data[..]).parse_with::<Utf16Le>(3).err(), Some(Error::InvalidEncoding));
+}
+
+#[test]
+fn legacy_strings() {
+    let mut buffer = &b"caf\x8E!"[..];
+    let string = buffer.parse_with::<LegacyStr>((4, Encoding::MacRoman)).unwrap();
+    assert_eq!(string.to_string(), "café");
+    assert_eq!(format!("{}", string), "café");
+    assert_eq!(buffer, b"!");
+}
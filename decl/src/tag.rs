@@ -0,0 +1,105 @@
+use std::fmt;
+
+use error::Error;
+use declarative::DeclResult;
+use declarative::Declarative;
+use declarative::StaticEncodingSize;
+use declarative::Tag;
+
+/// A four byte tag, such as an sfnt table tag or a RIFF chunk id.  Tags
+/// order by their bytes, which is the order of sfnt table directories.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag4([u8; 4]);
+
+impl Tag4 {
+    /// Constructs a tag from a byte literal, e.g. `Tag4::new(*b"cmap")`.
+    pub const fn new(bytes: [u8; 4]) -> Tag4 {
+        Tag4(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+}
+
+impl From<u32> for Tag4 {
+    fn from(tag: u32) -> Tag4 {
+        Tag4(tag.to_be_bytes())
+    }
+}
+
+impl<'buf> Declarative<'buf> for Tag4 {
+    fn parse(buffer: &'buf [u8]) -> DeclResult<'buf, Self> {
+        if buffer.len() < Self::SIZE {
+            return Err(Error::InsufficientBytes);
+        }
+
+        let dest = [buffer[0], buffer[1], buffer[2], buffer[3]];
+        Ok((Tag4(dest), &buffer[Self::SIZE..]))
+    }
+}
+
+impl StaticEncodingSize for Tag4 {
+    const SIZE: usize = 4;
+}
+
+impl Tag for Tag4 {
+    fn validate<'b>(&self, buffer: &'b [u8]) -> Result<&'b [u8], Error> {
+        Tag::validate(&self.0[..], buffer)
+    }
+}
+
+// Printable ASCII is written as is, anything else as a `\xNN` escape.
+// `Debug` quotes the tag, so it escapes quotes too.
+fn write_escaped(f: &mut fmt::Formatter, bytes: &[u8], quoted: bool) -> fmt::Result {
+    for &byte in bytes {
+        match byte {
+            b'\\' => write!(f, "\\\\")?,
+            b'"' if quoted => write!(f, "\\\"")?,
+            0x20..=0x7E => write!(f, "{}", byte as char)?,
+            _ => write!(f, "\\x{:02X}", byte)?,
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Tag4 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_escaped(f, &self.0, false)
+    }
+}
+
+impl fmt::Debug for Tag4 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Tag4(\"")?;
+        write_escaped(f, &self.0, true)?;
+        write!(f, "\")")
+    }
+}
+
+#[test]
+fn tag4_formatting_and_order() {
+    const CMAP: Tag4 = Tag4::new(*b"cmap");
+
+    assert_eq!(format!("{}", CMAP), "cmap");
+    assert_eq!(format!("{:?}", Tag4::new([b'O', b'S', b'/', 2])), "Tag4(\"OS/\\x02\")");
+    assert_eq!(format!("{:?}", Tag4::new(*b"a\"\\c")), "Tag4(\"a\\\"\\\\c\")");
+    assert_eq!(format!("{}", Tag4::new(*b"a\"\\c")), "a\"\\\\c");
+    assert_eq!(Tag4::from(0x636D6170), CMAP);
+    assert!(Tag4::new(*b"OS/2") < Tag4::new(*b"cmap"));
+    assert!(Tag4::new(*b"cmap") < Tag4::new(*b"glyf"));
+}
+
+#[test]
+fn tag4_array_search() {
+    use declarative::DeclRead;
+
+    let data = b"OS/2cmapglyfhead";
+    let tags = (&data[..]).parse_array::<Tag4>(4).unwrap();
+    assert_eq!(tags.binary_search(&Tag4::new(*b"glyf")), Ok(Ok(2)));
+    assert_eq!(tags.binary_search(&Tag4::new(*b"hhea")), Ok(Err(4)));
+}
@@ -0,0 +1,419 @@
+use std::marker::PhantomData;
+use std::fmt;
+
+use error::Error;
+use declarative::DeclRead;
+use declarative::DeclResult;
+use declarative::DeclarativeWithArgs;
+use declarative::DynamicEncodingSize;
+use owned::IntoOwned;
+
+/// Decides which element ends a `Terminated` array.  Implemented on
+/// `Sentinel` and on predicates `Fn(&Item) -> bool`.
+pub trait Terminator<Item> {
+    fn is_terminator(&self, item: &Item) -> bool;
+}
+
+/// Terminates an array at the first element equal to the given value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sentinel<T>(pub T);
+
+impl<T> Terminator<T> for Sentinel<T>
+where
+    T: PartialEq,
+{
+    fn is_terminator(&self, item: &T) -> bool {
+        self.0 == *item
+    }
+}
+
+impl<Item, F> Terminator<Item> for F
+where
+    F: Fn(&Item) -> bool,
+{
+    fn is_terminator(&self, item: &Item) -> bool {
+        self(item)
+    }
+}
+
+/// A lazy iterator over the elements preceding a terminating element.  The
+/// terminator is consumed but not yielded.  Elements need not have a static
+/// size, as they are parsed one after the other, but an element other than
+/// the terminator which takes no bytes is an `Error::InvalidEncoding`.
+pub struct TerminatedIter<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    start: &'buf [u8],
+    buffer: &'buf [u8],
+    terminator: T,
+    argument: Item::Argument,
+    done: bool,
+    failed: bool,
+    phantom: PhantomData<Item>,
+}
+
+impl<'buf, Item, T> TerminatedIter<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+    T: Terminator<Item>,
+{
+    pub fn new(buffer: &'buf [u8], terminator: T, argument: Item::Argument) -> Self {
+        TerminatedIter {
+            start: buffer,
+            buffer: buffer,
+            terminator: terminator,
+            argument: argument,
+            done: false,
+            failed: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of bytes consumed, including the terminator, once the
+    /// terminator has been reached.  `None` while iteration is incomplete or
+    /// if it stopped on an error.
+    pub fn consumed(&self) -> Option<usize> {
+        match self.done {
+            true => Some(self.start.len() - self.buffer.len()),
+            false => None,
+        }
+    }
+
+    /// The buffer following the terminator, once it has been reached.
+    pub fn remainder(&self) -> Option<&'buf [u8]> {
+        match self.done {
+            true => Some(self.buffer),
+            false => None,
+        }
+    }
+}
+
+impl<'buf, Item, T> Iterator for TerminatedIter<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+    T: Terminator<Item>,
+{
+    type Item = Result<Item, Error>;
+    fn next(&mut self) -> Option<Result<Item, Error>> {
+        if self.done || self.failed {
+            return None;
+        }
+
+        let mut buffer = self.buffer;
+        match buffer.parse_with::<Item>(self.argument.clone()) {
+            Ok(item) => {
+                let empty = buffer.len() == self.buffer.len();
+                self.buffer = buffer;
+                if self.terminator.is_terminator(&item) {
+                    self.done = true;
+                    return None;
+                }
+                // An empty element would be parsed again and again.
+                if empty {
+                    self.failed = true;
+                    return Some(Err(Error::InvalidEncoding));
+                }
+                Some(Ok(item))
+            },
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// An array which ends at a terminating element, such as a NUL terminated
+/// string or a list ending in `0xFFFF`.  Parsing walks the elements once to
+/// find the terminator, so that the buffer following it is known, after
+/// which the elements are iterated lazily again.
+pub struct Terminated<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    buffer: &'buf [u8],
+    length: usize,
+    size: usize,
+    terminator: T,
+    argument: Item::Argument,
+}
+
+impl<'buf, Item, T> fmt::Debug for Terminated<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Terminated {{ length: {} }}", self.length)
+    }
+}
+
+impl<'buf, Item, T> DeclarativeWithArgs<'buf> for Terminated<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+    T: Terminator<Item> + Clone,
+{
+    type Argument = (T, Item::Argument);
+    fn parse_with(
+        buffer: &'buf [u8],
+        arguments: (T, Item::Argument),
+    ) -> DeclResult<'buf, Self> {
+        let mut iter = TerminatedIter::<Item, T>::new(
+            buffer,
+            arguments.0.clone(),
+            arguments.1.clone(),
+        );
+
+        let mut length = 0usize;
+        while let Some(item) = iter.next() {
+            item?;
+            length += 1;
+        }
+
+        // The iterator has either found the terminator or failed above.
+        let size = iter.consumed().ok_or(Error::InsufficientBytes)?;
+
+        Ok((
+            Terminated {
+                buffer: buffer,
+                length: length,
+                size: size,
+                terminator: arguments.0,
+                argument: arguments.1,
+            },
+            &buffer[size..],
+        ))
+    }
+}
+
+impl<'buf, Item, T> Terminated<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+    T: Terminator<Item> + Clone,
+{
+    /// The number of elements, not counting the terminator.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn iter(&self) -> TerminatedIter<'buf, Item, T> {
+        TerminatedIter::new(self.buffer, self.terminator.clone(), self.argument.clone())
+    }
+}
+
+impl<'buf, Item, T> DynamicEncodingSize for Terminated<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<'buf, Item, T> IntoOwned for Terminated<'buf, Item, T>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    Item::Argument: Clone,
+    T: Terminator<Item> + Clone,
+{
+    type Owned = Vec<Item::Owned>;
+
+    fn into_owned(self) -> Result<Vec<Item::Owned>, Error> {
+        self.iter().map(|item| item?.into_owned()).collect()
+    }
+}
+
+/// A lazy iterator over elements which run to the end of the buffer.  An
+/// element which takes no bytes is an `Error::InvalidEncoding`.
+pub struct UntilEndIter<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    start: &'buf [u8],
+    buffer: &'buf [u8],
+    argument: Item::Argument,
+    failed: bool,
+    phantom: PhantomData<Item>,
+}
+
+impl<'buf, Item> UntilEndIter<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    pub fn new(buffer: &'buf [u8], argument: Item::Argument) -> Self {
+        UntilEndIter {
+            start: buffer,
+            buffer: buffer,
+            argument: argument,
+            failed: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of bytes consumed once the buffer has been exhausted.
+    /// `None` while iteration is incomplete or if it stopped on an error.
+    pub fn consumed(&self) -> Option<usize> {
+        match self.buffer.is_empty() && !self.failed {
+            true => Some(self.start.len()),
+            false => None,
+        }
+    }
+}
+
+impl<'buf, Item> Iterator for UntilEndIter<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    type Item = Result<Item, Error>;
+    fn next(&mut self) -> Option<Result<Item, Error>> {
+        if self.buffer.is_empty() || self.failed {
+            return None;
+        }
+
+        let length = self.buffer.len();
+        let dest = self.buffer.parse_with::<Item>(self.argument.clone());
+        if dest.is_err() {
+            self.failed = true;
+        } else if self.buffer.len() == length {
+            // An empty element would never reach the end.
+            self.failed = true;
+            return Some(Err(Error::InvalidEncoding));
+        }
+        Some(dest)
+    }
+}
+
+/// An array of elements which run to the end of the buffer, such as a list
+/// of chunks.  A trailing partial element is reported when iterated.
+pub struct UntilEnd<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    buffer: &'buf [u8],
+    argument: Item::Argument,
+}
+
+impl<'buf, Item> fmt::Debug for UntilEnd<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UntilEnd {{ size: {} }}", self.buffer.len())
+    }
+}
+
+impl<'buf, Item> DeclarativeWithArgs<'buf> for UntilEnd<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    type Argument = Item::Argument;
+    fn parse_with(
+        buffer: &'buf [u8],
+        argument: Item::Argument,
+    ) -> DeclResult<'buf, Self> {
+        Ok((
+            UntilEnd {
+                buffer: buffer,
+                argument: argument,
+            },
+            &buffer[buffer.len()..],
+        ))
+    }
+}
+
+impl<'buf, Item> UntilEnd<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item::Argument: Clone,
+{
+    pub fn iter(&self) -> UntilEndIter<'buf, Item> {
+        UntilEndIter::new(self.buffer, self.argument.clone())
+    }
+}
+
+impl<'buf, Item> DynamicEncodingSize for UntilEnd<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+{
+    fn size(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+impl<'buf, Item> IntoOwned for UntilEnd<'buf, Item>
+where
+    Item: DeclarativeWithArgs<'buf>,
+    Item: IntoOwned,
+    Item::Argument: Clone,
+{
+    type Owned = Vec<Item::Owned>;
+
+    fn into_owned(self) -> Result<Vec<Item::Owned>, Error> {
+        self.iter().map(|item| item?.into_owned()).collect()
+    }
+}
+
+#[test]
+fn terminated_arrays() {
+    let data = [b'a', b'b', 0, b'c'];
+    let mut buffer = &data[..];
+    let string = buffer.parse_with::<Terminated<u8, _>>((Sentinel(0u8), ())).unwrap();
+
+    assert_eq!(buffer, b"c");
+    assert_eq!(string.len(), 2);
+    assert_eq!(string.size(), 3);
+    assert_eq!(string.iter().collect::<Result<Vec<_>, _>>(), Ok(vec![b'a', b'b']));
+
+    let mut iter = TerminatedIter::<u16, _>::new(&[0x00, 0x01, 0xFF, 0xFF, 0x02], |item: &u16| *item == 0xFFFF, ());
+    assert_eq!(iter.next(), Some(Ok(1)));
+    assert_eq!(iter.consumed(), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.consumed(), Some(4));
+    assert_eq!(iter.remainder(), Some(&[0x02][..]));
+
+    let unterminated = (&data[..2]).parse_with::<Terminated<u8, _>>((Sentinel(0u8), ()));
+    assert_eq!(unterminated.err(), Some(Error::InsufficientBytes));
+}
+
+#[test]
+fn until_end_arrays() {
+    let data = [0x00, 0x01, 0x00, 0x02, 0x03];
+    let mut buffer = &data[..];
+    let array = buffer.parse_with::<UntilEnd<u16>>(()).unwrap();
+    assert!(buffer.is_empty());
+
+    let mut iter = array.iter();
+    assert_eq!(iter.next(), Some(Ok(1)));
+    assert_eq!(iter.next(), Some(Ok(2)));
+    assert_eq!(iter.next(), Some(Err(Error::InsufficientBytes)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.consumed(), None);
+
+    let mut iter = UntilEndIter::<u16>::new(&data[..4], ());
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.consumed(), Some(4));
+}
+
+#[test]
+fn empty_elements() {
+    let data = [0x01, 0x02];
+    let never = |_: &&[u8]| false;
+    let terminated = (&data[..]).parse_with::<Terminated<&[u8], _>>((never, 0));
+    assert_eq!(terminated.err(), Some(Error::InvalidEncoding));
+
+    let mut iter = UntilEndIter::<&[u8]>::new(&data[..], 0);
+    assert_eq!(iter.next(), Some(Err(Error::InvalidEncoding)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.consumed(), None);
+}
@@ -0,0 +1,633 @@
+extern crate decl;
+#[macro_use]
+extern crate decl_derive;
+
+use decl::array::Array;
+use decl::array::IndexedArray;
+use decl::declarative::DeclRead;
+use decl::declarative::DynamicEncodingSize;
+use decl::declarative::StaticEncodingSize;
+use decl::error::Error;
+use decl::offset::NullableOffset16;
+use decl::offset::Offset16;
+use decl::owned::IntoOwned;
+use decl::primitives::BeU16;
+use decl::primitives::LeU32;
+use decl::raw::Raw;
+use decl::span::Cursor;
+use decl::span::Spans;
+use decl::tag::Tag4;
+
+// The offset of a failure within `origin`, along with what failed.
+fn located<T>(result: Result<T, Error>, origin: &[u8]) -> Option<(usize, Error)> {
+    let error = result.err()?;
+    Some((error.position(origin)?, error.inner().clone()))
+}
+
+#[derive(Debug, Declarative)]
+struct Header<'buf> {
+    version: u16,
+    #[dropped(num_tables = "u16")]
+    #[length = "num_tables"]
+    tables: Array<'buf, BeU16>,
+    #[length = 2]
+    trailer: Array<'buf, u8>,
+}
+
+#[test]
+fn struct_with_arrays() {
+    let data = [0x00, 0x01, 0x00, 0x02, 0xAA, 0xAA, 0xBB, 0xBB, 0x01, 0x02, 0xFF];
+    let mut buffer = &data[..];
+    let header = buffer.parse::<Header>().unwrap();
+
+    assert_eq!(header.version, 1);
+    assert_eq!(header.tables.size(), 4);
+    assert_eq!(header.trailer.size(), 2);
+    assert_eq!(buffer, &[0xFF]);
+}
+
+#[derive(Declarative)]
+struct Ranges<'buf> {
+    count: u16,
+    #[length = "count"]
+    #[element_argument = "|_, _| ()"]
+    ranges: IndexedArray<'buf, u16>,
+}
+
+#[test]
+fn struct_with_element_arguments() {
+    let data = [0x00, 0x02, 0x00, 0x0A, 0x00, 0x0B];
+    let mut buffer = &data[..];
+    let ranges = buffer.parse::<Ranges>().unwrap();
+
+    assert_eq!(ranges.count, 2);
+    assert_eq!(ranges.ranges.get(1), Some(Ok(11)));
+    assert!(buffer.is_empty());
+}
+
+const TRUE: Tag4 = Tag4::new(*b"true");
+
+#[derive(Debug, Declarative)]
+struct OffsetTable {
+    #[tag(0x00010000u32, TRUE)]
+    num_tables: u16,
+    #[tag = b"\x00\x00"]
+    #[tag('k')]
+    #[tag("ey", "EY")]
+    key: u8,
+}
+
+#[test]
+fn struct_with_tags() {
+    let data = b"true\x00\x03\x00\x00key\x01";
+    let table = (&data[..]).parse::<OffsetTable>().unwrap();
+    assert_eq!(table.num_tables, 3);
+    assert_eq!(table.key, 1);
+
+    let data = b"\x00\x01\x00\x00\x00\x03\x00\x00kEY\x01";
+    assert!((&data[..]).parse::<OffsetTable>().is_ok());
+
+    let data = b"OTTO\x00\x03\x00\x00key\x01";
+    assert_eq!(located((&data[..]).parse::<OffsetTable>(), data), Some((0, Error::InvalidVersion)));
+}
+
+#[derive(Debug, PartialEq, Declarative, IntoOwned)]
+#[repr(u16)]
+enum PlatformId {
+    Unicode = 0,
+    Macintosh,
+    Windows = 3,
+    #[declarative(other)]
+    Unknown(u16),
+}
+
+#[derive(Debug, PartialEq, Declarative)]
+#[repr(u32)]
+#[declarative(wire = "LeU32")]
+enum Compression {
+    Stored = 0,
+    Deflated = 8,
+}
+
+#[derive(Debug, PartialEq, Declarative)]
+#[repr(u8)]
+enum Mid {
+    A = 1,
+    #[declarative(other)]
+    Unknown(u8),
+    B,
+}
+
+#[test]
+fn enum_from_integers() {
+    let data = [0x00, 0x01, 0x00, 0x03, 0x00, 0x07];
+    let ids = (&data[..]).parse_array::<PlatformId>(3).unwrap();
+    let ids = ids.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(ids, vec![PlatformId::Macintosh, PlatformId::Windows, PlatformId::Unknown(7)]);
+
+    let data = [0x08, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00];
+    let mut buffer = &data[..];
+    assert_eq!(buffer.parse::<Compression>(), Ok(Compression::Deflated));
+    assert_eq!(buffer.parse::<Compression>(), Err(Error::InvalidEncoding));
+    assert_eq!(<Compression as StaticEncodingSize>::SIZE, 4);
+
+    let data = [0x01, 0x02, 0x03];
+    let mids = (&data[..]).parse_array::<Mid>(3).unwrap();
+    let mids = mids.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(mids, vec![Mid::A, Mid::Unknown(2), Mid::B]);
+}
+
+#[derive(Declarative)]
+struct Lookups<'buf> {
+    first: Offset16<'buf, u8>,
+    #[relative_to(buffer = "&'buf [u8]")]
+    second: Offset16<'buf, u8>,
+    #[dropped(count = "u16")]
+    #[length = "count"]
+    rest: Array<'buf, Offset16<'buf, u8>>,
+}
+
+#[test]
+fn struct_with_offsets() {
+    let data = [0x00, 0x08, 0x00, 0x06, 0x00, 0x01, 0x00, 0x09, 0xAA, 0xBB];
+    let lookups = (&data[..]).parse::<Lookups>().unwrap();
+
+    assert_eq!(lookups.first.get(), Ok(0xAA));
+    // Relative to the second offset itself, at position 2.
+    assert_eq!(lookups.second.get(), Ok(0xAA));
+    assert_eq!(lookups.rest.get(0).unwrap().and_then(|offset| offset.get()), Ok(0xBB));
+}
+
+#[derive(Declarative)]
+#[declarative(arguments = "count: u16, parent: &'buf [u8]")]
+struct Child<'buf> {
+    #[length = "count"]
+    values: Array<'buf, u8>,
+    #[relative_to(parent = "&'buf [u8]")]
+    name: Offset16<'buf, u8>,
+}
+
+#[derive(Declarative)]
+struct Parent<'buf> {
+    #[dropped(count = "u16")]
+    #[argument(count = "u16", buffer = "&'buf [u8]")]
+    child: Child<'buf>,
+}
+
+#[test]
+fn struct_with_arguments() {
+    let data = [0x00, 0x02, 0x0A, 0x0B, 0x00, 0x04, 0xCC];
+    let mut buffer = &data[..];
+    let parent = buffer.parse::<Parent>().unwrap();
+
+    assert_eq!(parent.child.values.get(1), Some(Ok(0x0B)));
+    // Relative to the start of the child, at position 2.
+    assert_eq!(parent.child.name.get(), Ok(0xCC));
+    assert_eq!(buffer, &[0xCC]);
+}
+
+#[derive(Declarative)]
+struct Versioned {
+    version: u16,
+    width: u16,
+    #[declarative(condition = "version >= 2")]
+    height: Option<u16>,
+    #[declarative(condition = "version >= 3")]
+    #[tag = b"\xFF"]
+    depth: Option<u8>,
+}
+
+#[test]
+fn struct_with_conditions() {
+    let mut buffer = &[0x00, 0x01, 0x00, 0x02, 0xEE][..];
+    let v1 = buffer.parse::<Versioned>().unwrap();
+    assert_eq!((v1.version, v1.width, v1.height, v1.depth), (1, 2, None, None));
+    assert_eq!(buffer, &[0xEE]);
+
+    let v2 = (&[0x00, 0x02, 0x00, 0x02, 0x00, 0x03][..]).parse::<Versioned>().unwrap();
+    assert_eq!((v2.height, v2.depth), (Some(3), None));
+
+    let v3 = (&[0x00, 0x03, 0x00, 0x02, 0x00, 0x03, 0xFF, 0x04][..]).parse::<Versioned>().unwrap();
+    assert_eq!((v3.height, v3.depth), (Some(3), Some(4)));
+
+    let truncated = [0x00, 0x02, 0x00, 0x02, 0x00];
+    assert_eq!(located((&truncated[..]).parse::<Versioned>(), &truncated), Some((4, Error::InsufficientBytes)));
+}
+
+fn is_even(value: &u16) -> Result<(), Error> {
+    match value & 1 == 0 {
+        true => Ok(()),
+        false => Err(Error::InvalidValue { field: "seg_count_x2", value: Some(i128::from(*value)) }),
+    }
+}
+
+fn check_limits(limits: &Limits) -> Result<(), Error> {
+    match limits.units_per_em < 1000 && limits.reserved == 0 {
+        true => Ok(()),
+        false => Err(Error::InvalidValue { field: "units_per_em", value: None }),
+    }
+}
+
+#[derive(Debug, Declarative)]
+#[declarative(validate = "check_limits")]
+struct Limits {
+    #[range = "16..=16384"]
+    units_per_em: u16,
+    #[declarative(validate = "is_even")]
+    seg_count_x2: u16,
+    #[must_be(0)]
+    reserved: i8,
+}
+
+#[test]
+fn struct_with_validation() {
+    let limits = (&[0x00, 0x10, 0x00, 0x04, 0x00][..]).parse::<Limits>().unwrap();
+    assert_eq!((limits.units_per_em, limits.seg_count_x2, limits.reserved), (16, 4, 0));
+
+    let small = [0x00, 0x0F, 0x00, 0x04, 0x00];
+    assert_eq!(
+        located((&small[..]).parse::<Limits>(), &small),
+        Some((0, Error::InvalidValue { field: "units_per_em", value: Some(15) }))
+    );
+
+    let odd = [0x00, 0x10, 0x00, 0x05, 0x00];
+    assert_eq!(
+        located((&odd[..]).parse::<Limits>(), &odd),
+        Some((2, Error::InvalidValue { field: "seg_count_x2", value: Some(5) }))
+    );
+
+    let reserved = [0x00, 0x10, 0x00, 0x04, 0xFF];
+    assert_eq!(
+        located((&reserved[..]).parse::<Limits>(), &reserved),
+        Some((4, Error::InvalidValue { field: "reserved", value: Some(-1) }))
+    );
+
+    // The struct's own `validate` is located at its start.
+    let large = [0x04, 0x00, 0x00, 0x04, 0x00];
+    assert_eq!(
+        located((&large[..]).parse::<Limits>(), &large),
+        Some((0, Error::InvalidValue { field: "units_per_em", value: None }))
+    );
+}
+
+#[derive(Debug, Declarative)]
+struct WrappedLimits {
+    #[range = "16..=16384"]
+    units_per_em: BeU16,
+    #[must_be(0)]
+    reserved: LeU32,
+}
+
+#[test]
+fn checks_on_wrappers() {
+    let limits = (&[0x00, 0x10, 0x00, 0x00, 0x00, 0x00][..]).parse::<WrappedLimits>().unwrap();
+    assert_eq!((u16::from(limits.units_per_em), u32::from(limits.reserved)), (16, 0));
+
+    let small = (&[0x00, 0x0F, 0x00, 0x00, 0x00, 0x00][..]).parse::<WrappedLimits>();
+    assert_eq!(
+        small.err().map(|error| error.inner().clone()),
+        Some(Error::InvalidValue { field: "units_per_em", value: Some(15) })
+    );
+
+    let reserved = (&[0x00, 0x10, 0x01, 0x00, 0x00, 0x00][..]).parse::<WrappedLimits>();
+    assert_eq!(
+        reserved.err().map(|error| error.inner().clone()),
+        Some(Error::InvalidValue { field: "reserved", value: Some(1) })
+    );
+}
+
+#[derive(Declarative)]
+struct Padded {
+    kind: u8,
+    #[pad_to(4)]
+    size: u16,
+    #[skip(1)]
+    #[reserved(2)]
+    flags: u8,
+}
+
+#[test]
+fn struct_with_padding() {
+    let data = [0x01, 0xEE, 0xEE, 0xEE, 0x00, 0x10, 0xEE, 0x00, 0x00, 0x80, 0xFF];
+    let mut buffer = &data[..];
+    let padded = buffer.parse::<Padded>().unwrap();
+    assert_eq!((padded.kind, padded.size, padded.flags), (1, 0x10, 0x80));
+    assert_eq!(buffer, &[0xFF]);
+
+    let reserved = [0x01, 0xEE, 0xEE, 0xEE, 0x00, 0x10, 0xEE, 0x00, 0x01, 0x80];
+    assert_eq!(
+        located((&reserved[..]).parse::<Padded>(), &reserved),
+        Some((7, Error::InvalidValue { field: "flags", value: None }))
+    );
+    assert_eq!(located((&data[..5]).parse::<Padded>(), &data), Some((4, Error::InsufficientBytes)));
+}
+
+#[test]
+fn skip_and_pad_to() {
+    let data = [0u8; 8];
+    let mut buffer = &data[..];
+    buffer.skip(3).unwrap();
+    buffer.pad_to(&data, 4).unwrap();
+    assert_eq!(buffer.len(), 4);
+    buffer.pad_to(&data, 4).unwrap();
+    assert_eq!(buffer.len(), 4);
+    assert_eq!(buffer.skip(5), Err(Error::InsufficientBytes));
+    assert_eq!(buffer.pad_to(&data, 0), Err(Error::InvalidEncoding));
+    assert_eq!(buffer.pad_to(&[0u8; 8], 4), Err(Error::InvalidEncoding));
+}
+
+#[derive(Declarative)]
+struct Chunk {
+    kind: u8,
+    length: u8,
+    #[size = "length"]
+    first: u16,
+    #[strict_size = 2]
+    second: u16,
+}
+
+#[test]
+fn struct_with_sizes() {
+    let data = [0x01, 0x04, 0x00, 0x05, 0xEE, 0xEE, 0x00, 0x06, 0xFF];
+    let mut buffer = &data[..];
+    let chunk = buffer.parse::<Chunk>().unwrap();
+    assert_eq!((chunk.kind, chunk.length, chunk.first, chunk.second), (1, 4, 5, 6));
+    assert_eq!(buffer, &[0xFF]);
+
+    let short = [0x01, 0x01, 0x00, 0x05, 0x00, 0x06];
+    assert_eq!(located((&short[..]).parse::<Chunk>(), &short), Some((2, Error::InsufficientBytes)));
+}
+
+#[derive(Declarative)]
+struct Record<'buf> {
+    #[raw = "Chunk"]
+    chunk: &'buf [u8],
+    header: Raw<'buf, u16>,
+    #[rest]
+    payload: &'buf [u8],
+}
+
+#[test]
+fn struct_with_raw_bytes() {
+    let data = [0x01, 0x02, 0x00, 0x05, 0x00, 0x06, 0x00, 0x07, 0xAA, 0xBB];
+    let mut buffer = &data[..];
+    let record = buffer.parse::<Record>().unwrap();
+
+    assert_eq!(record.chunk, &data[..6]);
+    assert_eq!((*record.header, record.header.bytes()), (7, &data[6..8]));
+    assert_eq!(record.payload, &[0xAA, 0xBB]);
+    assert!(buffer.is_empty());
+}
+
+#[derive(Declarative)]
+struct Located<'buf> {
+    #[tag = b"\xFF"]
+    first: u16,
+    #[spans]
+    spans: Spans<'buf>,
+    #[declarative(condition = "first == 0")]
+    second: Option<u8>,
+    third: u8,
+}
+
+#[test]
+fn struct_with_spans() {
+    let data = [0xEE, 0xFF, 0x00, 0x01, 0x02];
+    let mut cursor = Cursor::new(&data);
+    cursor.skip(1).unwrap();
+    let located = cursor.parse::<Located>().unwrap();
+
+    assert_eq!((located.first, located.second, located.third), (1, None, 2));
+    assert_eq!(located.spans.span("first", &data), Some(2..4));
+    assert_eq!(located.spans.span("second", &data), None);
+    assert_eq!(located.spans.get("third"), Some(&data[4..]));
+    assert_eq!(cursor.position(), 5);
+}
+
+#[derive(Debug, Declarative)]
+struct Nested {
+    kind: u8,
+    glyph: Glyph,
+}
+
+#[test]
+fn nested_errors_are_located() {
+    let nested = (&[0x01, 0x00, 0x05, 0x00, 0x07][..]).parse::<Nested>().unwrap();
+    assert_eq!((nested.kind, nested.glyph), (1, Glyph { id: 5, advance: 7 }));
+
+    let data = [0xEE, 0x01, 0x00, 0x05, 0x00];
+    let mut cursor = Cursor::new(&data);
+    cursor.skip(1).unwrap();
+
+    // The failure is in the nested `Glyph`'s `advance`, not where the
+    // `Nested` started, which is where the cursor is left.
+    let error = cursor.parse::<Nested>().unwrap_err();
+    assert_eq!(cursor.error_position(&error), Some(4));
+    assert_eq!(error.inner(), &Error::InsufficientBytes);
+    assert_eq!(cursor.position(), 1);
+
+    let error = cursor.parse_array::<u32>(2).unwrap_err();
+    assert_eq!(cursor.error_position(&error), Some(1));
+}
+
+#[derive(Debug, Declarative)]
+#[declarative(size = 13)]
+struct Record13<'buf> {
+    #[tag = b"RC"]
+    #[dropped(version = "u16")]
+    pair: (u8, BeU16),
+    #[skip(1)]
+    offset: Offset16<'buf, u8>,
+    #[reserved(2)]
+    last: u8,
+}
+
+#[test]
+fn struct_with_static_size() {
+    assert_eq!(Record13::SIZE, 13);
+    assert_eq!(<(u8, u16, (u32, u64))>::SIZE, 15);
+    assert_eq!(Limits::SIZE, 5);
+
+    let data = [
+        b'R', b'C', 0x00, 0x01, 0x07, 0x00, 0x08, 0xEE, 0x00, 0x00, 0x00, 0x00, 0x00,
+        b'R', b'C', 0x00, 0x01, 0x09, 0x00, 0x0A, 0xEE, 0x00, 0x01, 0x00, 0x00, 0x00,
+    ];
+    let records = (&data[..]).parse_array::<Record13>(2).unwrap();
+    let second = records.get(1).unwrap().unwrap();
+    assert_eq!(((second.pair.0, u16::from(second.pair.1)), second.last), ((9, 0x0A), 0));
+    assert_eq!(second.offset.get(), Ok(b'C'));
+}
+
+#[test]
+fn struct_offset_constants() {
+    assert_eq!((Header::OFFSET_VERSION, Header::OFFSET_NUM_TABLES, Header::OFFSET_TABLES), (0, 2, 4));
+    assert_eq!((Record13::OFFSET_VERSION, Record13::OFFSET_PAIR, Record13::OFFSET_OFFSET), (2, 4, 8));
+    assert_eq!(Record13::OFFSET_LAST, 12);
+    assert_eq!((Located::OFFSET_FIRST, Located::OFFSET_SECOND), (1, 3));
+}
+
+#[derive(Declarative)]
+#[declarative(lazy)]
+struct Directory<'buf> {
+    #[tag = b"DR"]
+    version: u16,
+    #[dropped(count = "u16")]
+    #[length = "count"]
+    entries: Array<'buf, Offset16<'buf, u8>>,
+    #[range = "1..=9"]
+    flags: u8,
+}
+
+#[test]
+fn lazy_views() {
+    let data = [b'D', b'R', 0x00, 0x01, 0x00, 0x02, 0x00, 0x0B, 0x00, 0x0C, 0x03, 0xAA, 0xBB, 0xFF];
+    let mut buffer = &data[..];
+    let view = buffer.parse::<DirectoryView>().unwrap();
+    assert_eq!(buffer, &data[11..]);
+
+    assert_eq!(view.version(), Ok(1));
+    assert_eq!(view.count(), Ok(2));
+    let entries = view.entries().unwrap();
+    assert_eq!(entries.get(1).unwrap().and_then(|offset| offset.get()), Ok(0xBB));
+    assert_eq!(view.flags(), Ok(3));
+
+    let eager = (&data[..]).parse::<Directory>().unwrap();
+    assert_eq!((eager.version, eager.entries.len(), eager.flags), (1, 2, 3));
+
+    let bad_tag = [b'D', b'X', 0x00, 0x01, 0x00, 0x00, 0x03];
+    assert_eq!(located((&bad_tag[..]).parse::<DirectoryView>(), &bad_tag), Some((0, Error::InvalidVersion)));
+    assert_eq!(located((&data[..5]).parse::<DirectoryView>(), &data), Some((4, Error::InsufficientBytes)));
+}
+
+#[derive(Declarative)]
+#[declarative(lazy)]
+struct Chunks<'buf> {
+    #[dropped(first_len = "u8")]
+    #[length = "first_len"]
+    first: Array<'buf, u8>,
+    #[dropped(second_len = "u8")]
+    #[length = "second_len"]
+    second: Array<'buf, u8>,
+    #[tag = b"E"]
+    last: u8,
+}
+
+#[test]
+fn lazy_views_after_dynamic_fields() {
+    let data = [0x01, 0xAA, 0x02, 0xBB, 0xCC, b'E', 0x07, 0xFF];
+    let mut buffer = &data[..];
+    let view = buffer.parse::<ChunksView>().unwrap();
+    assert_eq!(buffer, &[0xFF]);
+
+    assert_eq!(view.first().unwrap().get(0), Some(Ok(0xAA)));
+    assert_eq!(view.second_len(), Ok(2));
+    assert_eq!(view.second().unwrap().get(1), Some(Ok(0xCC)));
+    assert_eq!(view.last(), Ok(7));
+
+    let eager = (&data[..]).parse::<Chunks>().unwrap();
+    assert_eq!((eager.first.len(), eager.second.len(), eager.last), (1, 2, 7));
+
+    let bad_tag = [0x01, 0xAA, 0x02, 0xBB, 0xCC, b'X', 0x07];
+    assert_eq!(located((&bad_tag[..]).parse::<ChunksView>(), &bad_tag), Some((5, Error::InvalidVersion)));
+    assert_eq!(located((&data[..4]).parse::<ChunksView>(), &data), Some((3, Error::InsufficientBytes)));
+}
+
+#[derive(Debug, PartialEq, Declarative, IntoOwned)]
+#[owned(derive(Debug, Clone, PartialEq))]
+struct Glyph {
+    id: u16,
+    advance: u16,
+}
+
+#[derive(Declarative, IntoOwned)]
+struct Font<'buf> {
+    tag: Tag4,
+    platform: PlatformId,
+    glyph: Offset16<'buf, Glyph>,
+    kerning: NullableOffset16<'buf, Glyph>,
+    #[dropped(count = "u16")]
+    #[length = "count"]
+    widths: Array<'buf, BeU16>,
+    #[rest]
+    trailer: &'buf [u8],
+}
+
+#[test]
+fn owned_conversion() {
+    let font = {
+        let data = vec![b'f', b'o', b'n', b't', 0x00, 0x03, 0x00, 0x10, 0x00, 0x00, 0x00, 0x02,
+                        0x01, 0x00, 0x02, 0x00, 0x00, 0x05, 0x00, 0x07, 0xEE];
+        let font = (&data[..]).parse::<Font>().unwrap();
+        font.into_owned().unwrap()
+    };
+
+    assert_eq!(font.tag, Tag4::new(*b"font"));
+    assert_eq!(font.platform, PlatformId::Windows);
+    assert_eq!(font.glyph, Box::new(GlyphOwned { id: 5, advance: 7 }));
+    assert_eq!(font.kerning, None);
+    assert_eq!(font.trailer, vec![0x00, 0x05, 0x00, 0x07, 0xEE]);
+    let widths = std::thread::spawn(move || font.widths).join().unwrap();
+    assert_eq!(widths, vec![BeU16::from(0x0100), BeU16::from(0x0200)]);
+
+    // The offset is only followed when converting.
+    let data = [b'f', b'o', b'n', b't', 0x00, 0x03, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00];
+    let font = (&data[..]).parse::<Font>().unwrap();
+    assert_eq!(located(font.into_owned(), &data), Some((12, Error::InsufficientBytes)));
+}
+
+#[derive(Declarative)]
+struct Eager {
+    #[dropped(count = "u16")]
+    #[length = "count"]
+    widths: Vec<BeU16>,
+    #[offset = "Offset16"]
+    glyph: Box<Glyph>,
+    #[length = 2]
+    #[offset = "Offset16"]
+    glyphs: Vec<Glyph>,
+}
+
+#[derive(Declarative)]
+#[declarative(size = 4)]
+struct GlyphRef {
+    id: u16,
+    #[offset = "Offset16"]
+    glyph: Box<Glyph>,
+}
+
+#[test]
+fn eager_vecs_and_boxes() {
+    let data = [0x00, 0x02, 0x00, 0x01, 0x00, 0x02, 0x00, 0x0C, 0x00, 0x10, 0x00, 0x0C,
+                0x00, 0x05, 0x00, 0x07, 0x00, 0x09, 0x00, 0x01];
+    let eager = (&data[..]).parse::<Eager>().unwrap();
+    assert_eq!(eager.widths, vec![BeU16::from(1), BeU16::from(2)]);
+    assert_eq!(eager.glyph, Box::new(Glyph { id: 5, advance: 7 }));
+    assert_eq!(eager.glyphs, vec![Glyph { id: 9, advance: 1 }, Glyph { id: 5, advance: 7 }]);
+
+    let data = [0x00, 0x01, 0x00, 0x04, 0x00, 0x05, 0x00, 0x07];
+    let glyph_ref = (&data[..]).parse::<GlyphRef>().unwrap();
+    assert_eq!((glyph_ref.id, glyph_ref.glyph.advance), (1, 7));
+    assert_eq!((GlyphRef::OFFSET_GLYPH, GlyphRef::SIZE), (2, 4));
+
+    // Offsets are resolved while parsing, so a bad one fails the struct.
+    let data = [0x00, 0x01, 0x00, 0x04, 0x00, 0x05];
+    assert_eq!(located((&data[..]).parse::<GlyphRef>(), &data), Some((6, Error::InsufficientBytes)));
+}
+
+#[derive(Declarative)]
+struct Shadowing {
+    argument: u8,
+    value: u8,
+    result: u8,
+    #[length = "argument"]
+    #[declarative(condition = "value == result")]
+    rest: Option<Vec<u8>>,
+}
+
+#[test]
+fn fields_named_like_generated_locals() {
+    let data = [2, 1, 1, 0xAA, 0xBB];
+    let parsed = (&data[..]).parse::<Shadowing>().unwrap();
+    assert_eq!((parsed.argument, parsed.value, parsed.result), (2, 1, 1));
+    assert_eq!(parsed.rest, Some(vec![0xAA, 0xBB]));
+}
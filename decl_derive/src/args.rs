@@ -7,30 +7,97 @@ use syn::MetaItem;
 
 // Maintain a collection of arguments. Keep track if this is for an Array
 // which requires special formatting.
+//
+// No arguments are passed as `()`, a single argument as itself, and more
+// than one as a tuple.
 #[derive(Default)]
-struct Arguments<'a> {
-    is_array: Option<ArrayLength<'a>>,
-    args: Vec<Argument<'a>>,
+pub struct Arguments<'a> {
+    pub is_array: Option<ArrayLength<'a>>,
+    pub args: Vec<Argument<'a>>,
 }
 
 impl<'a> Arguments<'a> {
+    // The argument is a tuple unless there is exactly one.
+    fn is_tuple(&self) -> bool {
+        self.args.len() != 1
+    }
+
     fn split(&self) -> (ArgumentsDeclaration, ArgumentsDefinition, ArgumentsInvoke) {
         (ArgumentsDeclaration(self), ArgumentsDefinition(self), ArgumentsInvoke(self))
     }
 
-    fn declaration(&self) -> ArgumentsDeclaration {
+    pub fn declaration(&self) -> ArgumentsDeclaration {
         ArgumentsDeclaration(self)
     }
 
-    fn definition(&self) -> ArgumentsDefinition {
+    pub fn definition(&self) -> ArgumentsDefinition {
         ArgumentsDefinition(self)
     }
 
-    fn invoke(&self) -> ArgumentsInvoke {
+    pub fn invoke(&self) -> ArgumentsInvoke {
         ArgumentsInvoke(self)
     }
 
-    fn parse_arguments(items: &[NestedMetaItem]) -> Arguments {
+    // The values alone, without the array length.
+    pub fn to_tokens_values(&self, tokens: &mut Tokens) {
+        if self.is_tuple() {
+            tokens.append("(");
+        }
+        for arg in &self.args {
+            arg.to_tokens_value(tokens);
+            if self.is_tuple() {
+                tokens.append(",");
+            }
+        }
+        if self.is_tuple() {
+            tokens.append(")");
+        }
+    }
+
+    fn to_tokens_types(&self, tokens: &mut Tokens) {
+        if self.is_tuple() {
+            tokens.append("(");
+        }
+        for arg in &self.args {
+            arg.to_tokens_type(tokens);
+            if self.is_tuple() {
+                tokens.append(",");
+            }
+        }
+        if self.is_tuple() {
+            tokens.append(")");
+        }
+    }
+
+    // Parses `"<ident>: <type>, ..."`, as given to
+    // `#[declarative(arguments = "...")]`.  Commas inside brackets belong to
+    // the type, and the `>` of a `->` closes no bracket.
+    pub fn from_str(arguments: &'a str) -> Arguments<'a> {
+        let mut result = Arguments::default();
+        let mut depth = 0;
+        let mut start = 0;
+        let mut previous = ' ';
+        for (index, c) in arguments.char_indices() {
+            let arrow = previous == '-' && c == '>';
+            previous = c;
+            match c {
+                '>' if arrow => {},
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' => depth -= 1,
+                ',' if depth == 0 => {
+                    result.args.push(Argument::from_str(&arguments[start..index]));
+                    start = index + 1;
+                },
+                _ => {},
+            }
+        }
+        if !arguments[start..].trim().is_empty() {
+            result.args.push(Argument::from_str(&arguments[start..]));
+        }
+        result
+    }
+
+    pub fn parse_arguments(items: &[NestedMetaItem]) -> Arguments {
         let mut arguments = Arguments::default();
         for item in items {
             let item = match *item {
@@ -46,87 +113,97 @@ impl<'a> Arguments<'a> {
     }
 }
 
-struct ArgumentsDefinition<'a>(&'a Arguments<'a>);
+pub struct ArgumentsDefinition<'a>(&'a Arguments<'a>);
 
 impl<'a> ToTokens for ArgumentsDefinition<'a> {
     fn to_tokens(&self, tokens: &mut Tokens) {
-        tokens.append("(");
         if self.0.is_array.is_some() {
-            tokens.append("usize, (");
-        }
-        for arg in &self.0.args {
-            arg.to_tokens_type(tokens);
-            tokens.append(",");
+            tokens.append("(");
+            tokens.append("usize,");
         }
+        self.0.to_tokens_types(tokens);
         if self.0.is_array.is_some() {
             tokens.append(")");
         }
-        tokens.append(")");
     }
 }
 
-struct ArgumentsDeclaration<'a>(&'a Arguments<'a>);
+pub struct ArgumentsDeclaration<'a>(&'a Arguments<'a>);
 
 impl<'a> ToTokens for ArgumentsDeclaration<'a> {
     fn to_tokens(&self, tokens: &mut Tokens) {
+        let length = match self.0.is_array {
+            Some(ref length) => length,
+            None => return self.0.to_tokens_values(tokens),
+        };
+
         tokens.append("(");
-        if let Some(ref length) = self.0.is_array {
-            length.to_tokens_value(tokens);
-            tokens.append(",(");
+        length.to_tokens_value(tokens);
+        if !self.0.is_tuple() {
+            tokens.append(",");
+            self.0.to_tokens_values(tokens);
+            tokens.append(")");
+            return;
         }
+
+        tokens.append(",(");
         for arg in &self.0.args {
             arg.to_tokens_value(tokens);
             tokens.append(",");
         }
-        if self.0.is_array.is_some() {
-            tokens.append(")");
-        }
+        tokens.append(")");
         tokens.append(")");
     }
 }
 
-struct ArgumentsInvoke<'a>(&'a Arguments<'a>);
+pub struct ArgumentsInvoke<'a>(&'a Arguments<'a>);
 
 impl <'a> ToTokens for ArgumentsInvoke<'a> {
     fn to_tokens(&self, tokens: &mut Tokens) {
-        tokens.append("(");
         if let Some(ref length) = self.0.is_array {
+            tokens.append("(");
             length.to_tokens_value(tokens);
-            tokens.append(" as usize, (");
-        }
-        for arg in &self.0.args {
-            arg.to_tokens_value(tokens);
-            tokens.append(",");
+            tokens.append(" as usize,");
         }
+        self.0.to_tokens_values(tokens);
         if self.0.is_array.is_some() {
             tokens.append(")");
         }
-        tokens.append(")");
     }
 }
 
 // Used to construct arguments for the struct/enum as a
 //   #[declarative(arguments = "buffer: &'buf [u8]")]
 // or for a field, like
-//   #[argument("first: u32", "second: &'buf [u8]")]
+//   #[argument(first = "u32", second = "&'buf [u8]")]
 //
 // Arguments must be referencable, either by being passed
 // as an argument, a previous field, or a #[dropped(...)] attribute.
-struct Argument<'a> {
-    ident: &'a str,
-    ty: &'a str,
+pub struct Argument<'a> {
+    pub ident: &'a str,
+    pub ty: &'a str,
 }
 
 impl<'a> Argument<'a> {
-    fn to_tokens_value(&self, tokens: &mut Tokens) {
+    pub fn to_tokens_value(&self, tokens: &mut Tokens) {
         tokens.append(self.ident);
     }
 
-    fn to_tokens_type(&self, tokens: &mut Tokens) {
+    pub fn to_tokens_type(&self, tokens: &mut Tokens) {
         tokens.append(self.ty);
     }
 
-    fn from_meta_item(item: &MetaItem) -> Argument {
+    fn from_str(argument: &'a str) -> Argument<'a> {
+        match argument.find(':') {
+            Some(colon) => Argument {
+                ident: argument[..colon].trim(),
+                ty: argument[colon + 1..].trim(),
+            },
+            None => panic!("arguments must be a list of `<ident>: <type>`. Got: `{}`", argument),
+        }
+    }
+
+    pub fn from_meta_item(item: &MetaItem) -> Argument {
         match *item {
             MetaItem::NameValue(ref ident, ref lit) => {
                 let ty = match *lit {
@@ -146,13 +223,13 @@ impl<'a> Argument<'a> {
 
 // Array lengths may either referenced a variable that has been parsed,
 // or have constant size.
-enum ArrayLength<'a> {
+pub enum ArrayLength<'a> {
     Variable(&'a str),
     Constant(usize),
 }
 
 impl<'a> ArrayLength<'a> {
-    fn to_tokens_value(&self, tokens: &mut Tokens) {
+    pub fn to_tokens_value(&self, tokens: &mut Tokens) {
         match *self {
             ArrayLength::Variable(ref name) => tokens.append(name),
             ArrayLength::Constant(size) => size.to_tokens(tokens),
@@ -163,7 +240,7 @@ impl<'a> ArrayLength<'a> {
         tokens.append("usize");
     }
 
-    fn from_lit(lit: &Lit) -> ArrayLength {
+    pub fn from_lit(lit: &Lit) -> ArrayLength {
         match *lit {
             Lit::Str(ref ident, _) => ArrayLength::Variable(ident),
             Lit::Int(size, _) => ArrayLength::Constant(size as usize),
@@ -222,3 +299,26 @@ fn arguments_array_printing() {
          function ( ( num_glyphs  as usize, ( buffer , num_tables , ) ) ) ;",
         tokens.as_str());
 }
+
+#[test]
+fn arguments_single_printing() {
+    let arguments = Arguments::from_str("offsets: Array<'buf, u16>");
+
+    let (def, dec, inv) = arguments.split();
+    let tokens = quote!( let #def : #dec; function( #inv ); );
+    assert_eq!(
+        "let offsets : Array<'buf, u16> ; function ( offsets ) ;",
+        tokens.as_str());
+}
+
+#[test]
+fn arguments_from_str() {
+    let arguments = Arguments::from_str("table: &'buf [u8], counts: (u16, u16),");
+    let args: Vec<(&str, &str)> = arguments.args.iter().map(|arg| (arg.ident, arg.ty)).collect();
+    assert_eq!(vec![("table", "&'buf [u8]"), ("counts", "(u16, u16)")], args);
+    assert!(Arguments::from_str("").args.is_empty());
+
+    let arguments = Arguments::from_str("f: fn(u8) -> u16, g: u32, h: Box<Fn(u8) -> Vec<u8>>");
+    let args: Vec<(&str, &str)> = arguments.args.iter().map(|arg| (arg.ident, arg.ty)).collect();
+    assert_eq!(vec![("f", "fn(u8) -> u16"), ("g", "u32"), ("h", "Box<Fn(u8) -> Vec<u8>>")], args);
+}
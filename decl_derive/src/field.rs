@@ -0,0 +1,653 @@
+use quote::ToTokens;
+use quote::Tokens;
+
+use syn;
+use syn::Lit;
+use syn::MetaItem;
+use syn::NestedMetaItem;
+
+use args::Argument;
+use args::Arguments;
+use args::ArrayLength;
+use tag::Tags;
+
+// Parsed before the field, in the order the attributes appear.
+pub enum Preamble<'a> {
+    Tag(Tags<'a>),
+    Dropped(Argument<'a>),
+    // `#[skip(n)]`
+    Skip(ArrayLength<'a>),
+    // `#[reserved(n)]`, which are skipped but must be zero, along with the
+    // name of the field they precede for the error.
+    Reserved(ArrayLength<'a>, &'a str),
+    // `#[pad_to(align)]`, from the start of the struct, or
+    // `#[pad_to(align, relative_to = "file")]`.
+    PadTo(usize, &'a str),
+}
+
+// Checked after the field is parsed, failing with `Error::InvalidValue`,
+// or the error `validate` returns.
+pub enum Check<'a> {
+    // `#[declarative(validate = "path::to::fn")]`, a
+    // `fn(&T) -> Result<(), Error>` like the struct's own `validate`.
+    Validate(&'a str),
+    // `#[range = "16..=16384"]`, as syn can't read a range in an attribute.
+    Range(&'a str),
+    // `#[must_be(0)]`
+    MustBe(&'a Lit),
+}
+
+// A struct field along with the parsing attributes attached to it.
+pub struct Field<'a> {
+    pub ident: &'a syn::Ident,
+    pub ty: &'a syn::Ty,
+    pub preamble: Vec<Preamble<'a>>,
+    pub arguments: Arguments<'a>,
+    pub element_argument: Option<&'a str>,
+    pub relative_to: Option<Argument<'a>>,
+    pub condition: Option<&'a str>,
+    pub checks: Vec<Check<'a>>,
+    // `#[size = n]` or `#[strict_size = n]`, and the `decl::bounded` type
+    // that confines the field to those bytes.
+    pub size: Option<(ArrayLength<'a>, &'static str)>,
+    // `#[rest]`, which passes the length of the rest of the buffer.
+    pub rest: bool,
+    // `#[raw = "Type"]`, which parses a `Type` but keeps its bytes.
+    pub raw: Option<&'a str>,
+    // `#[spans]`, which isn't parsed but holds the bytes of the others.
+    pub spans: bool,
+    // `#[offset = "Offset16"]`, which resolves a `Box` field (or each
+    // element of a `Vec`) through an offset of that type.
+    pub offset: Option<&'a str>,
+    // Whether the bytes of the field are recorded for a `#[spans]` field.
+    pub record_span: bool,
+}
+
+impl<'a> Field<'a> {
+    pub fn from_field(field: &'a syn::Field) -> Field<'a> {
+        let ident = field.ident.as_ref()
+            .expect("only structs with named fields can derive `Declarative`");
+
+        let mut result = Field {
+            ident: ident,
+            ty: &field.ty,
+            preamble: Vec::new(),
+            arguments: Arguments::default(),
+            element_argument: None,
+            relative_to: None,
+            condition: None,
+            checks: Vec::new(),
+            size: None,
+            rest: false,
+            raw: None,
+            spans: false,
+            offset: None,
+            record_span: false,
+        };
+
+        for attr in &field.attrs {
+            match attr.value {
+                MetaItem::NameValue(ref name, ref lit) if name == "length" => {
+                    result.arguments.is_array = Some(ArrayLength::from_lit(lit));
+                },
+                MetaItem::Word(ref name) if name == "rest" => result.rest = true,
+                MetaItem::Word(ref name) if name == "spans" => result.spans = true,
+                MetaItem::NameValue(ref name, ref lit) if name == "raw" => {
+                    match *lit {
+                        Lit::Str(ref ty, _) => result.raw = Some(ty),
+                        _ => panic!("`raw` must be a string containing the type to parse"),
+                    }
+                },
+                MetaItem::NameValue(ref name, ref lit) if name == "offset" => {
+                    match *lit {
+                        Lit::Str(ref offset, _) if offset_size(offset).is_some() => result.offset = Some(offset),
+                        _ => panic!("`offset` must be one of \"Offset8\", \"Offset16\", \"Offset32\" or \"Offset64\""),
+                    }
+                },
+                MetaItem::NameValue(ref name, ref lit) if name == "size" => {
+                    result.size = Some((ArrayLength::from_lit(lit), "Bounded"));
+                },
+                MetaItem::NameValue(ref name, ref lit) if name == "strict_size" => {
+                    result.size = Some((ArrayLength::from_lit(lit), "Exact"));
+                },
+                MetaItem::List(ref name, _) | MetaItem::NameValue(ref name, _) if name == "tag" => {
+                    result.preamble.push(Preamble::Tag(Tags::from_meta_item(&attr.value)));
+                },
+                MetaItem::List(ref name, ref items) if name == "dropped" => {
+                    for item in items {
+                        match *item {
+                            NestedMetaItem::MetaItem(ref item) => {
+                                let dropped = Argument::from_meta_item(item);
+                                result.preamble.push(Preamble::Dropped(dropped));
+                            },
+                            NestedMetaItem::Literal(_) =>
+                                panic!("dropped fields must be a list of `<ident> = \"<type>\"`"),
+                        }
+                    }
+                },
+                MetaItem::NameValue(ref name, ref lit) if name == "element_argument" => {
+                    match *lit {
+                        Lit::Str(ref expr, _) => result.element_argument = Some(expr),
+                        _ => panic!("`element_argument` must be a string containing a closure"),
+                    }
+                },
+                MetaItem::List(ref name, ref items) if name == "argument" => {
+                    result.arguments.args = Arguments::parse_arguments(items).args;
+                },
+                MetaItem::List(ref name, ref items) if name == "declarative" => {
+                    for item in items {
+                        match *item {
+                            NestedMetaItem::MetaItem(
+                                MetaItem::NameValue(ref name, Lit::Str(ref expr, _))
+                            ) if name == "condition" => result.condition = Some(expr),
+                            NestedMetaItem::MetaItem(
+                                MetaItem::NameValue(ref name, Lit::Str(ref path, _))
+                            ) if name == "validate" => result.checks.push(Check::Validate(path)),
+                            _ => panic!("unexpected `#[declarative(...)]` item on field `{}`: `{:?}`",
+                                ident, item),
+                        }
+                    }
+                },
+                MetaItem::List(ref name, ref items) if name == "skip" || name == "reserved" => {
+                    let count = match items.as_slice() {
+                        [NestedMetaItem::Literal(ref lit)] => ArrayLength::from_lit(lit),
+                        _ => panic!("`{}` must be a single count", name),
+                    };
+                    result.preamble.push(match name.as_ref() {
+                        "skip" => Preamble::Skip(count),
+                        _ => Preamble::Reserved(count, ident.as_ref()),
+                    });
+                },
+                MetaItem::List(ref name, ref items) if name == "pad_to" => {
+                    let (align, base) = match items.as_slice() {
+                        [NestedMetaItem::Literal(Lit::Int(align, _))] => (align, TABLE_START),
+                        [
+                            NestedMetaItem::Literal(Lit::Int(align, _)),
+                            NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, Lit::Str(ref base, _))),
+                        ] if name == "relative_to" => (align, base.as_str()),
+                        _ => panic!("`pad_to` must be `#[pad_to(<align>)]` or \
+                                     `#[pad_to(<align>, relative_to = \"<ident>\")]`"),
+                    };
+                    if *align == 0 {
+                        panic!("`pad_to` on field `{}` must align to more than zero bytes", ident);
+                    }
+                    result.preamble.push(Preamble::PadTo(*align as usize, base));
+                },
+                MetaItem::NameValue(ref name, ref lit) if name == "range" => {
+                    match *lit {
+                        Lit::Str(ref range, _) => result.checks.push(Check::Range(range)),
+                        _ => panic!("`range` must be a string containing a range, e.g. `\"16..=16384\"`"),
+                    }
+                },
+                MetaItem::List(ref name, ref items) if name == "must_be" => {
+                    match items.as_slice() {
+                        [NestedMetaItem::Literal(ref lit)] => result.checks.push(Check::MustBe(lit)),
+                        _ => panic!("`must_be` must be a single literal"),
+                    }
+                },
+                MetaItem::List(ref name, ref items) if name == "relative_to" => {
+                    match items.as_slice() {
+                        [NestedMetaItem::MetaItem(ref item)] =>
+                            result.relative_to = Some(Argument::from_meta_item(item)),
+                        _ => panic!("`relative_to` must be a single `<ident> = \"<type>\"`"),
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        if result.element_argument.is_some() && result.arguments.is_array.is_none() {
+            panic!("`element_argument` on field `{}` requires a `length`", ident);
+        }
+
+        if result.rest && (result.arguments.is_array.is_some() || !result.arguments.args.is_empty()) {
+            panic!("`rest` on field `{}` can't be combined with a `length` or `argument`", ident);
+        }
+
+        if result.condition.is_some() && option_type(result.ty).is_none() {
+            panic!("conditional field `{}` must be an `Option`", ident);
+        }
+
+        if result.is_vec() && result.arguments.is_array.is_none() {
+            panic!("`Vec` field `{}` requires a `length`", ident);
+        }
+
+        let boxed = last_segment(result.parsed_ty()).map_or(false, |segment| segment.ident == "Box");
+        if result.offset.is_some() && !result.is_vec() && !boxed {
+            panic!("`offset` on field `{}` requires it to be a `Box` or a `Vec`", ident);
+        }
+
+        result
+    }
+
+    // The type that is parsed, which is wrapped in an `Option` when the field
+    // is conditional.
+    fn parsed_ty(&self) -> &'a syn::Ty {
+        match self.condition {
+            Some(_) => option_type(self.ty).unwrap(),
+            None => self.ty,
+        }
+    }
+
+    // Offsets are relative to the start of the struct unless the field says
+    // otherwise.  Only offsets, and arrays of them, take a base.
+    fn base(&self) -> Option<&'a str> {
+        match self.relative_to {
+            Some(ref base) => Some(base.ident),
+            None if self.is_offset() => Some(TABLE_START),
+            None => None,
+        }
+    }
+
+    // Whether the field is a `Vec`, which is parsed as an array.
+    fn is_vec(&self) -> bool {
+        last_segment(self.parsed_ty()).map_or(false, |segment| segment.ident == "Vec")
+    }
+
+    // The size of the offset a single `Box` is read through.
+    pub fn offset_size(&self) -> Option<usize> {
+        match self.arguments.is_array {
+            Some(_) => None,
+            None => self.offset.and_then(offset_size),
+        }
+    }
+
+    // The type that is read from the buffer, before a `Vec` or `Box` field
+    // is converted from the `Array` or offset it was read as.
+    fn wire_ty(&self) -> Tokens {
+        let mut tokens = Tokens::new();
+        if let Some(raw) = self.raw {
+            tokens.append("::decl::raw::Raw<");
+            tokens.append(raw);
+            tokens.append(">");
+            return tokens;
+        }
+        if !self.is_vec() && self.offset.is_none() {
+            let ty = self.parsed_ty();
+            return quote!(#ty);
+        }
+
+        // The element of a `Vec`, or the item in a `Box`.
+        let item_ty = element_type(self.parsed_ty()).unwrap();
+        let mut item = Tokens::new();
+        match self.offset {
+            Some(offset) => {
+                item.append("::decl::offset::");
+                item.append(offset);
+                item.append_all(&[quote!(<#item_ty>)]);
+            },
+            None => item.append_all(&[quote!(#item_ty)]),
+        }
+
+        match (self.is_vec(), self.element_argument) {
+            (true, Some(_)) => tokens.append_all(&[quote!(::decl::array::IndexedArray<#item>)]),
+            (true, None) => tokens.append_all(&[quote!(::decl::array::Array<#item>)]),
+            (false, _) => return item,
+        }
+        tokens
+    }
+
+    // Converts `value` from the type read to the type of the field, for
+    // `Vec` and `Box` fields.
+    fn eager(&self) -> Tokens {
+        match (self.is_vec(), self.offset.is_some()) {
+            (true, true) => quote!(
+                let value = ::decl::array::CollectChecked::collect_checked(
+                    value.iter().map(|offset| offset.and_then(|offset| offset.get())))
+                    .map_err(|error| error.at(__field))?;
+            ),
+            (true, false) => quote!(let value = value.to_vec().map_err(|error| error.at(__field))?;),
+            (false, true) => quote!(let value = Box::new(value.get().map_err(|error| error.at(__field))?);),
+            (false, false) => Tokens::new(),
+        }
+    }
+
+    fn is_offset(&self) -> bool {
+        if self.offset.is_some() {
+            return true;
+        }
+        if self.arguments.is_array.is_some() {
+            element_type(self.parsed_ty()).map_or(false, is_offset)
+        } else {
+            is_offset(self.parsed_ty())
+        }
+    }
+
+    // Whether the field needs the start of the struct to be kept.
+    pub fn uses_table_start(&self) -> bool {
+        let padded = self.preamble.iter().any(|preamble| match *preamble {
+            Preamble::PadTo(_, base) => base == TABLE_START,
+            _ => false,
+        });
+        padded || self.base() == Some(TABLE_START)
+    }
+
+    // The size of the preamble as an expression, if it's known before parsing.
+    pub fn preamble_size(&self) -> Option<Tokens> {
+        let mut tokens = Tokens::new();
+        tokens.append("0");
+        for preamble in &self.preamble {
+            tokens.append("+");
+            tokens.append_all(&[preamble.static_size()?]);
+        }
+        Some(tokens)
+    }
+
+    // The size of the field itself as an expression, if it's known before
+    // parsing.  `#[spans]` fields take no bytes.
+    pub fn value_size(&self) -> Option<Tokens> {
+        if self.spans {
+            return Some(quote!(0));
+        }
+
+        let dynamic = self.condition.is_some() || self.rest || self.raw.is_some() ||
+            self.size.is_some() || self.arguments.is_array.is_some();
+        match dynamic {
+            true => None,
+            false if self.offset.is_some() => {
+                let size = self.offset_size().unwrap();
+                Some(quote!(#size))
+            },
+            false => {
+                let ty = self.ty;
+                Some(quote!(<#ty as ::decl::declarative::StaticEncodingSize>::SIZE))
+            },
+        }
+    }
+
+    // The types which must be `StaticEncodingSize` for the sizes to hold.
+    pub fn static_types(&self) -> Vec<Tokens> {
+        let mut types = Vec::new();
+        for preamble in &self.preamble {
+            if let Preamble::Dropped(ref dropped) = *preamble {
+                let mut ty = Tokens::new();
+                dropped.to_tokens_type(&mut ty);
+                types.push(ty);
+            }
+        }
+        if !self.spans && self.offset_size().is_none() {
+            let ty = self.ty;
+            types.push(quote!(#ty));
+        }
+        types
+    }
+
+    // The expressions written in the field's attributes, which may refer to
+    // earlier fields.
+    pub fn references(&self) -> Vec<&'a str> {
+        let mut references = Vec::new();
+        let counts = self.preamble.iter().filter_map(|preamble| match *preamble {
+            Preamble::Skip(ref count) | Preamble::Reserved(ref count, _) => Some(count),
+            _ => None,
+        });
+        for count in counts.chain(self.arguments.is_array.as_ref()).chain(self.size.as_ref().map(|size| &size.0)) {
+            if let ArrayLength::Variable(expr) = *count {
+                references.push(expr);
+            }
+        }
+        references.extend(self.arguments.args.iter().map(|arg| arg.ident));
+        references.extend(self.relative_to.as_ref().map(|base| base.ident));
+        references.extend(self.condition);
+        references.extend(self.element_argument);
+        references
+    }
+
+    // The identifiers in the expressions of `references`.
+    pub fn referenced_identifiers(&self) -> Vec<&'a str> {
+        self.references().into_iter().flat_map(identifiers).collect()
+    }
+
+    // The names of the `#[dropped]` values before the field.
+    pub fn dropped_names(&self) -> Vec<&'a str> {
+        self.preamble.iter().filter_map(|preamble| match *preamble {
+            Preamble::Dropped(ref dropped) => Some(dropped.ident),
+            _ => None,
+        }).collect()
+    }
+
+    // The argument of a single item: the field's own, or each element's.
+    fn item_argument(&self, tokens: &mut Tokens) {
+        if let Some(base) = self.base() {
+            tokens.append("(");
+            tokens.append(base);
+            tokens.append(",");
+        }
+        self.arguments.to_tokens_values(tokens);
+        if self.base().is_some() {
+            tokens.append(")");
+        }
+    }
+}
+
+// The identifiers in an expression written in an attribute.
+fn identifiers(expr: &str) -> Vec<&str> {
+    expr.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+// The name the generated parser gives the buffer at the start of the struct.
+pub const TABLE_START: &'static str = "__table";
+
+fn last_segment(ty: &syn::Ty) -> Option<&syn::PathSegment> {
+    match *ty {
+        syn::Ty::Path(_, ref path) => path.segments.last(),
+        _ => None,
+    }
+}
+
+fn offset_size(offset: &str) -> Option<usize> {
+    match offset {
+        "Offset8" => Some(1),
+        "Offset16" => Some(2),
+        "Offset32" => Some(4),
+        "Offset64" => Some(8),
+        _ => None,
+    }
+}
+
+fn is_offset(ty: &syn::Ty) -> bool {
+    match last_segment(ty) {
+        Some(segment) => match segment.ident.as_ref() {
+            "Offset8" | "Offset16" | "Offset32" | "Offset64" => true,
+            "NullableOffset8" | "NullableOffset16" | "NullableOffset32" | "NullableOffset64" => true,
+            _ => false,
+        },
+        None => false,
+    }
+}
+
+fn option_type(ty: &syn::Ty) -> Option<&syn::Ty> {
+    match last_segment(ty) {
+        Some(segment) if segment.ident == "Option" => element_type(ty),
+        _ => None,
+    }
+}
+
+// The first type parameter of an array type, which is its element.
+fn element_type(ty: &syn::Ty) -> Option<&syn::Ty> {
+    match last_segment(ty).map(|segment| &segment.parameters) {
+        Some(&syn::PathParameters::AngleBracketed(ref data)) => data.types.first(),
+        _ => None,
+    }
+}
+
+impl<'a> Preamble<'a> {
+    // The size as an expression, if it's known before parsing.
+    pub fn static_size(&self) -> Option<Tokens> {
+        let mut tokens = Tokens::new();
+        match *self {
+            Preamble::Tag(ref tags) => tags.static_size()?.to_tokens(&mut tokens),
+            Preamble::Dropped(ref dropped) => {
+                tokens.append("<");
+                dropped.to_tokens_type(&mut tokens);
+                tokens.append("as ::decl::declarative::StaticEncodingSize>::SIZE");
+            },
+            Preamble::Skip(ArrayLength::Constant(count)) |
+            Preamble::Reserved(ArrayLength::Constant(count), _) => count.to_tokens(&mut tokens),
+            _ => return None,
+        }
+        Some(tokens)
+    }
+}
+
+impl<'a> ToTokens for Preamble<'a> {
+    fn to_tokens(&self, tokens: &mut Tokens) {
+        match *self {
+            Preamble::Tag(ref tags) => tags.to_tokens(tokens),
+            Preamble::Dropped(ref dropped) => {
+                tokens.append("let");
+                dropped.to_tokens_value(tokens);
+                tokens.append("= ::decl::declarative::DeclRead::parse_with::<");
+                dropped.to_tokens_type(tokens);
+                tokens.append(">(&mut buffer, ())?;");
+            },
+            Preamble::Skip(ref count) => {
+                tokens.append("::decl::declarative::DeclRead::skip(&mut buffer,");
+                count.to_tokens_value(tokens);
+                tokens.append("as usize)?;");
+            },
+            Preamble::Reserved(ref count, field) => {
+                tokens.append("{ let reserved = buffer; ::decl::declarative::DeclRead::skip(&mut buffer,");
+                count.to_tokens_value(tokens);
+                tokens.append("as usize)?; if reserved[..reserved.len() - buffer.len()].iter().any(|&byte| byte != 0) {");
+                tokens.append_all(&[quote!(
+                    return Err(::decl::error::Error::InvalidValue { field: #field, value: None }.at(reserved));
+                )]);
+                tokens.append("} }");
+            },
+            Preamble::PadTo(align, base) => {
+                tokens.append("::decl::declarative::DeclRead::pad_to(&mut buffer,");
+                tokens.append(base);
+                tokens.append(",");
+                align.to_tokens(tokens);
+                tokens.append(")?;");
+            },
+        }
+    }
+}
+
+impl<'a> Check<'a> {
+    // Returns early if `value`, the field named `field`, fails the check.
+    // Ranges and `must_be` compare the value as an `i128`, so they work on
+    // any integer that converts into one, such as `BeU16`.
+    fn tokens(&self, field: &str) -> Tokens {
+        let mut tokens = Tokens::new();
+        if let Check::Validate(path) = *self {
+            tokens.append(path);
+            tokens.append("(&value).map_err(|error| error.at(__field))?;");
+            return tokens;
+        }
+
+        tokens.append("{ let value: i128 = ::std::convert::Into::into(value); if !");
+        match *self {
+            Check::Range(range) => {
+                tokens.append("(");
+                tokens.append(range);
+                tokens.append(").contains(&value)");
+            },
+            Check::MustBe(lit) => {
+                tokens.append("(value ==");
+                lit.to_tokens(&mut tokens);
+                tokens.append(")");
+            },
+            Check::Validate(_) => unreachable!(),
+        }
+        tokens.append_all(&[quote!({
+            return Err(::decl::error::Error::InvalidValue { field: #field, value: Some(value) }.at(__field));
+        })]);
+        tokens.append("}");
+        tokens
+    }
+}
+
+// Emits the statements that validate tags and parse dropped fields before
+// the field, then parse the field into a local binding of the same name.
+// The argument is evaluated first, as it may refer to `buffer`.  A
+// conditional field and its preamble are only parsed when the condition
+// holds, and are `None` otherwise.
+impl<'a> ToTokens for Field<'a> {
+    fn to_tokens(&self, tokens: &mut Tokens) {
+        if self.spans {
+            return;
+        }
+
+        let ident = self.ident;
+        let ty = self.wire_ty();
+        let mut invoke = Tokens::new();
+        if self.rest {
+            invoke.append("buffer.len()");
+        } else if let Some(ref length) = self.arguments.is_array {
+            invoke.append("(");
+            length.to_tokens_value(&mut invoke);
+            invoke.append(" as usize,");
+        }
+        if !self.rest {
+            match self.element_argument {
+                Some(expr) => {
+                    invoke.append("::decl::array::argument_fn(");
+                    invoke.append(expr);
+                    invoke.append(")");
+                },
+                None => self.item_argument(&mut invoke),
+            }
+        }
+        if self.arguments.is_array.is_some() {
+            invoke.append(")");
+        }
+        let raw = match self.raw {
+            Some(_) => quote!(let value = value.bytes();),
+            None => Tokens::new(),
+        };
+        let (span_start, span_end) = match self.record_span {
+            true => {
+                let name = ident.as_ref();
+                (quote!(let __span = buffer;),
+                 quote!(__spans.push((#name, &__span[..__span.len() - buffer.len()]));))
+            },
+            false => (Tokens::new(), Tokens::new()),
+        };
+
+        let preamble = &self.preamble;
+        let name = ident.as_ref();
+        let checks = self.checks.iter().map(|check| check.tokens(name));
+        let read = match self.size {
+            Some((ref size, bounded)) => {
+                let mut read = Tokens::new();
+                read.append("let argument = (");
+                size.to_tokens_value(&mut read);
+                read.append("as usize, argument);");
+                read.append("let value = ::decl::declarative::DeclRead::parse_with::<::decl::bounded::");
+                read.append(bounded);
+                read.append_all(&[quote!(<#ty>>(&mut buffer, argument)?.into_inner();)]);
+                read
+            },
+            None => quote!(
+                let value = ::decl::declarative::DeclRead::parse_with::<#ty>(&mut buffer, argument)?;
+            ),
+        };
+        let eager = self.eager();
+        let parse = quote!(
+            let __field = buffer;
+            #span_start
+            let argument = #invoke;
+            #read
+            #raw
+            #eager
+            #(#checks)*
+            #span_end
+            value
+        );
+
+        match self.condition {
+            Some(condition) => {
+                tokens.append("let");
+                ident.to_tokens(tokens);
+                tokens.append("= if");
+                tokens.append(condition);
+                tokens.append_all(&[quote!({ #(#preamble)* Some({ #parse }) } else { None };)]);
+            },
+            None => tokens.append_all(&[quote!(#(#preamble)* let #ident = { #parse };)]),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use quote::ToTokens;
+use quote::Tokens;
+
+use syn;
+
+use args::ArrayLength;
+use field::Field;
+use field::Preamble;
+
+// One line of the layout: a field, or something parsed before one.
+struct Row {
+    name: String,
+    ty: String,
+    // `OFFSET_<NAME>`, for fields and dropped fields.
+    constant: Option<syn::Ident>,
+    // The size as an expression, if it's known before parsing.
+    size: Option<Tokens>,
+    // The size, if the derive can tell it from the type alone.
+    known: Option<usize>,
+    // The type which must be `StaticEncodingSize` for `size` to hold.
+    bound: Option<Tokens>,
+}
+
+// The size of the common fixed size types, going by their names.
+fn known_size(ty: &syn::Ty) -> Option<usize> {
+    match *ty {
+        syn::Ty::Tup(ref types) => types.iter().map(known_size).sum(),
+        syn::Ty::Path(None, ref path) => {
+            let name = path.segments.last()?.ident.as_ref();
+            let bits = match name {
+                "u8" | "i8" => 8,
+                "u16" | "i16" => 16,
+                "u32" | "i32" => 32,
+                "u64" | "i64" => 64,
+                "Tag4" => 32,
+                _ if name.starts_with("Offset") => name["Offset".len()..].parse().ok()?,
+                _ if name.starts_with("NullableOffset") => name["NullableOffset".len()..].parse().ok()?,
+                _ if name.len() > 3 && ["Be", "Le", "Ne"].contains(&&name[..2]) &&
+                    ["U", "I"].contains(&&name[2..3]) => name[3..].parse().ok()?,
+                _ => return None,
+            };
+            Some(bits / 8)
+        },
+        _ => None,
+    }
+}
+
+// Types are printed by quote with spaces between every token.
+fn type_name(ty: &str) -> String {
+    ty.replace(" < ", "<").replace(" <", "<").replace(" >", ">").replace(" , ", ", ")
+        .replace("& ", "&").replace("( ", "(").replace(" )", ")").replace(" ,)", ",)")
+}
+
+fn offset_constant(name: &str) -> syn::Ident {
+    syn::Ident::new(format!("OFFSET_{}", name.to_uppercase()))
+}
+
+fn constant(count: &ArrayLength) -> Option<usize> {
+    match *count {
+        ArrayLength::Constant(count) => Some(count),
+        ArrayLength::Variable(_) => None,
+    }
+}
+
+fn rows(field: &Field) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for preamble in &field.preamble {
+        let (name, ty, constant, known, bound) = match *preamble {
+            Preamble::Tag(ref tags) => {
+                ("*tag*".to_string(), tags.describe(), None, tags.static_size(), None)
+            },
+            Preamble::Dropped(ref dropped) => {
+                let mut ty = Tokens::new();
+                dropped.to_tokens_type(&mut ty);
+                let known = syn::parse_type(dropped.ty).ok().and_then(|ty| known_size(&ty));
+                (dropped.ident.to_string(), dropped.ty.to_string(),
+                 Some(offset_constant(dropped.ident)), known, Some(ty))
+            },
+            Preamble::Skip(ref count) => ("*skipped*".to_string(), String::new(), None, constant(count), None),
+            Preamble::Reserved(ref count, _) => ("*reserved*".to_string(), String::new(), None, constant(count), None),
+            Preamble::PadTo(..) => ("*padding*".to_string(), String::new(), None, None, None),
+        };
+        let size = preamble.static_size();
+        rows.push(Row { name: name, ty: ty, constant: constant, size: size, known: known, bound: bound });
+    }
+
+    let ty = field.ty;
+    let size = field.value_size();
+    rows.push(Row {
+        name: field.ident.to_string(),
+        ty: type_name(quote!(#ty).as_str()),
+        constant: match field.spans {
+            true => None,
+            false => Some(offset_constant(field.ident.as_ref())),
+        },
+        known: match field.spans {
+            true => Some(0),
+            false => size.as_ref().and_then(|_| field.offset_size().or_else(|| known_size(ty))),
+        },
+        bound: match field.spans || field.offset_size().is_some() {
+            true => None,
+            false => Some(quote!(#ty)),
+        },
+        size: size,
+    });
+    rows
+}
+
+fn cell(value: Option<usize>, is_static: bool) -> String {
+    match (value, is_static) {
+        (Some(value), true) => value.to_string(),
+        (None, true) => "?".to_string(),
+        (_, false) => "-".to_string(),
+    }
+}
+
+// Emits an `OFFSET_<FIELD>` constant for every field (and dropped field)
+// until the first whose size isn't known before parsing.  The constants
+// are grouped into impls bounded on the types before them being
+// `StaticEncodingSize`, as in `derive_static_size`, and the first holds a
+// layout table for rustdoc.
+pub fn derive_layout(ident: &syn::Ident, generics: &syn::Generics, fields: &[Field]) -> Tokens {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = &where_clause.predicates;
+
+    let mut doc = format!("Layout of `{}`, in bytes.\n\n\
+                           | Offset | Size | Type | Field |\n\
+                           |-------:|-----:|------|-------|\n", ident);
+    let mut groups: Vec<(usize, Vec<Tokens>)> = Vec::new();
+    let mut offset = quote!(0);
+    let mut known = Some(0);
+    let mut bounds: Vec<Tokens> = Vec::new();
+    let mut is_static = true;
+
+    for row in fields.iter().flat_map(rows) {
+        let row_static = is_static && row.size.is_some();
+        doc.push_str(&format!("| {} | {} | {} | {} |\n",
+            cell(known, is_static), cell(row.known, row_static),
+            match row.ty.is_empty() {
+                true => String::new(),
+                false => format!("`{}`", row.ty),
+            },
+            match row.constant {
+                Some(_) => format!("`{}`", row.name),
+                None => row.name.clone(),
+            }));
+
+        if !is_static {
+            continue;
+        }
+
+        if let Some(constant) = row.constant {
+            let field_doc = format!("The offset of `{}`.", row.name);
+            let item = quote!(#[doc = #field_doc] pub const #constant: usize = #offset;);
+            match groups.last_mut() {
+                Some(&mut (count, ref mut items)) if count == bounds.len() => items.push(item),
+                _ => groups.push((bounds.len(), vec![item])),
+            }
+        }
+
+        match row.size {
+            Some(size) => {
+                offset = quote!(#offset + #size);
+                known = match (known, row.known) {
+                    (Some(known), Some(size)) => Some(known + size),
+                    _ => None,
+                };
+                bounds.extend(row.bound);
+            },
+            None => is_static = false,
+        }
+    }
+
+    if groups.first().map_or(true, |&(count, _)| count != 0) {
+        groups.insert(0, (0, Vec::new()));
+    }
+
+    let impls = groups.into_iter().enumerate().map(|(index, (count, items))| {
+        let doc = match index {
+            0 => quote!(#[doc = #doc]),
+            _ => Tokens::new(),
+        };
+        let bounds = &bounds[..count];
+        quote!(
+            #doc
+            impl #impl_generics #ident #ty_generics
+            where
+                #(#predicates,)*
+                #(for<'__size> #bounds: ::decl::declarative::StaticEncodingSize,)*
+            {
+                #(#items)*
+            }
+        )
+    });
+
+    quote!(#(#impls)*)
+}
+
+#[test]
+fn layout_table() {
+    let input = syn::parse_derive_input("
+        struct Header<'buf> {
+            #[tag = b\"OTTO\"]
+            version: BeU16,
+            #[dropped(num_tables = \"u16\")]
+            #[length = \"num_tables\"]
+            tables: Array<'buf, (u16, Offset32<'buf, Table>)>,
+            #[skip(2)]
+            last: u8,
+        }").unwrap();
+    let fields: Vec<Field> = match input.body {
+        syn::Body::Struct(ref data) => data.fields().iter().map(Field::from_field).collect(),
+        _ => unreachable!(),
+    };
+
+    let layout = derive_layout(&input.ident, &input.generics, &fields);
+    let doc = "Layout of `Header`, in bytes.\\n\\n\
+               | Offset | Size | Type | Field |\\n\
+               |-------:|-----:|------|-------|\\n\
+               | 0 | 4 | `b\\\"OTTO\\\"` | *tag* |\\n\
+               | 4 | 2 | `BeU16` | `version` |\\n\
+               | 6 | 2 | `u16` | `num_tables` |\\n\
+               | 8 | - | `Array<'buf, (u16, Offset32<'buf, Table>)>` | `tables` |\\n\
+               | - | - |  | *skipped* |\\n\
+               | - | - | `u8` | `last` |\\n";
+    assert!(layout.as_str().contains(doc), "{}", layout.as_str());
+    assert!(layout.as_str().contains("pub const OFFSET_TABLES : usize = 0 + 4usize + < BeU16 as"));
+}
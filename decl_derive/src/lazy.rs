@@ -0,0 +1,192 @@
+use quote::ToTokens;
+use quote::Tokens;
+
+use syn;
+
+use field::Field;
+use field::Preamble;
+
+// `#[declarative(lazy)]` generates `<Name>View<'buf>`, which checks that the
+// fields of static size are present and their tags are valid when parsed,
+// and otherwise reads each field on demand through a getter of the same
+// name (dropped fields included).  A field starts at a constant offset while
+// the fields before it have a static size.  Otherwise it starts after the
+// end of the field before it, which is found by parsing that field once in
+// `parse` and kept in the view, so a getter only parses its own field.
+// Fields without a `length`, `size`, `condition`, `rest` or `raw` must be
+// `StaticEncodingSize`.
+pub fn derive_lazy(
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    lifetime: &syn::Lifetime,
+    fields: &[Field],
+) -> Tokens
+{
+    if !generics.ty_params.is_empty() {
+        panic!("lazy structs can't have type parameters");
+    }
+    if fields.iter().any(|field| field.spans) {
+        panic!("lazy structs can't have a `#[spans]` field");
+    }
+
+    let view = syn::Ident::new(format!("{}View", ident));
+    let mut names: Vec<&str> = Vec::new();
+    // The offset of the next field while it is constant, otherwise the
+    // function that finds the end of the field before it.
+    let mut start = Some(quote!(0));
+    let mut fixed = quote!(0);
+    let mut previous_end = None;
+    let mut methods = Vec::new();
+    // The statements in `parse` which check tags, and which fill in
+    // `starts` with the offsets of the fields after a dynamic one.
+    let mut checks = Vec::new();
+    let mut boundaries = Vec::new();
+    let mut slots = 0usize;
+
+    for field in fields {
+        let field_start = match (&start, &previous_end) {
+            (&Some(ref start), _) => start.clone(),
+            (&None, &Some(ref end)) => {
+                let slot = slots;
+                slots += 1;
+                boundaries.push(quote!(view.starts[#slot] = view.#end()?;));
+                quote!(self.starts[#slot])
+            },
+            (&None, &None) => unreachable!(),
+        };
+
+        let dropped = field.dropped_names();
+        let referenced = field.referenced_identifiers();
+        let dependencies = names.iter()
+            .filter(|name| referenced.contains(name) && !dropped.contains(name))
+            .map(|name| {
+                let name = syn::Ident::new(*name);
+                quote!(let #name = self.#name()?;)
+            });
+        let table = match field.uses_table_start() {
+            true => quote!(let __table = self.buffer;),
+            false => Tokens::new(),
+        };
+        // As in the eager parser, failures are located where `buffer` is
+        // left, unless a nested parser located them first.
+        let dependencies: Vec<Tokens> = dependencies.collect();
+        let dependencies = &dependencies;
+        let located = |ty: Tokens, body: Tokens| quote!(
+            let start = #field_start;
+            let mut buffer = match self.buffer.get(start..) {
+                Some(buffer) => buffer,
+                None => return Err(::decl::error::Error::InsufficientBytes.at(self.buffer)),
+            };
+            let __result = (|| -> Result<#ty, ::decl::error::Error> {
+                #table
+                #(#dependencies)*
+                #body
+            })();
+            __result.map_err(|error| error.at(buffer))
+        );
+
+        for (index, preamble) in field.preamble.iter().enumerate() {
+            if let Preamble::Dropped(ref argument) = *preamble {
+                let name = syn::Ident::new(argument.ident);
+                let mut ty = Tokens::new();
+                argument.to_tokens_type(&mut ty);
+                let preamble = &field.preamble[..index + 1];
+                let body = located(ty.clone(), quote!(#(#preamble)* Ok(#name)));
+                methods.push(quote!(
+                    pub fn #name(&self) -> Result<#ty, ::decl::error::Error> {
+                        #body
+                    }
+                ));
+            }
+        }
+
+        let name = field.ident;
+        let ty = field.ty;
+        let body = located(quote!(#ty), quote!(#field Ok(#name)));
+        methods.push(quote!(
+            pub fn #name(&self) -> Result<#ty, ::decl::error::Error> {
+                #body
+            }
+        ));
+
+        start = match (start, field.preamble_size(), field.value_size()) {
+            (Some(start), Some(preamble), Some(value)) => Some(quote!(#start + #preamble + #value)),
+            (Some(start), _, _) => {
+                fixed = start;
+                None
+            },
+            (None, _, _) => None,
+        };
+        if start.is_none() {
+            let end = syn::Ident::new(format!("__end_{}", name));
+            let body = located(quote!(usize), quote!(#field Ok(self.buffer.len() - buffer.len())));
+            methods.push(quote!(
+                #[allow(dead_code)]
+                fn #end(&self) -> Result<usize, ::decl::error::Error> {
+                    #body
+                }
+            ));
+            previous_end = Some(end);
+        } else if let Some(last) = field.preamble.iter().rposition(|preamble| match *preamble {
+            Preamble::Tag(_) => true,
+            _ => false,
+        }) {
+            // A field with a dynamic size has its tags checked when its end
+            // is found, and the others when the view is parsed.
+            let check = syn::Ident::new(format!("__check_{}", name));
+            let preamble = &field.preamble[..last + 1];
+            let body = located(quote!(()), quote!(#(#preamble)* Ok(())));
+            methods.push(quote!(
+                fn #check(&self) -> Result<(), ::decl::error::Error> {
+                    #body
+                }
+            ));
+            checks.push(quote!(view.#check()?;));
+        }
+
+        names.extend(dropped);
+        names.push(name.as_ref());
+    }
+
+    let end = match (start, previous_end) {
+        (Some(end), _) => {
+            fixed = end.clone();
+            end
+        },
+        (None, Some(end)) => quote!(view.#end()?),
+        (None, None) => unreachable!(),
+    };
+    let doc = format!("A view of a `{}`, which reads each field when it is asked for.", ident);
+
+    quote!(
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis struct #view<#lifetime> {
+            buffer: &#lifetime [u8],
+            starts: [usize; #slots],
+        }
+
+        impl<#lifetime> #view<#lifetime> {
+            #(#methods)*
+        }
+
+        impl<#lifetime> ::decl::declarative::Declarative<#lifetime> for #view<#lifetime> {
+            fn parse(buffer: &#lifetime [u8]) -> ::decl::declarative::DeclResult<#lifetime, Self> {
+                // The fields of static size are located at the start of the view.
+                if buffer.len() < #fixed {
+                    return Err(::decl::error::Error::InsufficientBytes.at(buffer));
+                }
+
+                let mut view = #view { buffer: buffer, starts: [0; #slots] };
+                #(#checks)*
+                #(#boundaries)*
+                let end = #end;
+                match buffer.get(end..) {
+                    Some(rest) => Ok((view, rest)),
+                    None => Err(::decl::error::Error::InsufficientBytes.at(buffer)),
+                }
+            }
+        }
+    )
+}
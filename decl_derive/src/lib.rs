@@ -9,8 +9,16 @@ use quote::Tokens;
 use quote::ToTokens;
 
 mod args;
+mod field;
+mod layout;
+mod lazy;
+mod owned;
+mod tag;
 
-#[proc_macro_derive(Declarative)]
+use args::Arguments;
+use field::Field;
+
+#[proc_macro_derive(Declarative, attributes(declarative, tag, length, dropped, element_argument, relative_to, argument, range, must_be, skip, reserved, pad_to, size, strict_size, rest, raw, spans, offset))]
 pub fn derive_declarative(input: TokenStream) -> TokenStream {
     let input = syn::parse_derive_input(&input.to_string())
         .expect("failed to parse derive input");
@@ -18,6 +26,23 @@ pub fn derive_declarative(input: TokenStream) -> TokenStream {
         .expect("failed to serialize rust")
 }
 
+#[proc_macro_derive(IntoOwned, attributes(owned))]
+pub fn derive_into_owned(input: TokenStream) -> TokenStream {
+    let input = syn::parse_derive_input(&input.to_string())
+        .expect("failed to parse derive input");
+    expand_derive_into_owned(input).parse()
+        .expect("failed to serialize rust")
+}
+
+fn expand_derive_into_owned(input: syn::DeriveInput) -> Tokens {
+    use syn::Body;
+
+    match input.body {
+        Body::Struct(ref s) => owned::derive_owned_struct(&input.vis, &input.ident, &input.generics, &input.attrs, s.fields()),
+        Body::Enum(_) => owned::derive_owned_enum(&input.ident, &input.generics),
+    }
+}
+
 fn expand_derive_declarative(input: syn::DeriveInput) -> Tokens {
     use syn::Body;
     use syn::VariantData;
@@ -26,31 +51,375 @@ fn expand_derive_declarative(input: syn::DeriveInput) -> Tokens {
     let generics = &input.generics;
 
     match input.body {
-        Body::Struct(ref s) => derive_declarative_struct(ident, generics, s.fields()),
-        Body::Enum(ref e) => unimplemented!(), 
+        Body::Struct(ref s) => derive_declarative_struct(ident, &input.vis, generics, &input.attrs, s.fields()),
+        Body::Enum(ref e) => derive_declarative_enum(ident, &input.attrs, e),
+    }
+}
+
+// The items of every `#[declarative(...)]` attribute in `attrs`.
+fn declarative_items(attrs: &[syn::Attribute]) -> Vec<&syn::MetaItem> {
+    let mut items = Vec::new();
+    for attr in attrs {
+        match attr.value {
+            syn::MetaItem::List(ref name, ref nested) if name == "declarative" => {
+                for item in nested {
+                    match *item {
+                        syn::NestedMetaItem::MetaItem(ref item) => items.push(item),
+                        syn::NestedMetaItem::Literal(ref lit) =>
+                            panic!("unexpected literal `{:?}` in `#[declarative(...)]`", lit),
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+    items
+}
+
+// The impl needs a lifetime for the buffer.  Types that borrow from the buffer
+// already declare one (the first lifetime is assumed to be it), otherwise
+// `'buf` is introduced for the impl only.
+fn buffer_lifetime(generics: &syn::Generics) -> (syn::Generics, syn::Lifetime) {
+    let mut generics = generics.clone();
+    if generics.lifetimes.is_empty() {
+        generics.lifetimes.push(syn::LifetimeDef::new("'buf"));
     }
+
+    let lifetime = generics.lifetimes[0].lifetime.clone();
+    (generics, lifetime)
 }
 
+// Structs taking `#[declarative(arguments = "<ident>: <type>, ...")]`
+// implement `DeclarativeWithArgs`, with the arguments bound by name before
+// the first field, otherwise they implement `Declarative`.  A struct
+// `#[declarative(validate = "path::to::fn")]` is a
+// `fn(&Self) -> Result<(), Error>` called once every field is parsed.
 fn derive_declarative_struct(
     ident: &syn::Ident, 
+    vis: &syn::Visibility,
     generics: &syn::Generics, 
+    attrs: &[syn::Attribute],
     variant: &[syn::Field],
 ) -> Tokens 
 {
-    unimplemented!()
-//     quote!(
-//         impl #impl_generics Declarative<'buf> for #ty #ty_generics #where_clause {
-//             type Argument = #argument;
-//             
-//             fn parse(buffer: &'buf [u8], argument: Self::Argument) -> DeclResult<'buf, Self> {
-//                 // Size check?
-//                 
-//                 // For each field:
-//                 //  - expand tags, and dropped in order
-//                 //  - check for specialty annotations (length, relative_to)
-//                 
-//                 // Generate type.
-//             }
-//         }
-//     )
+    let (impl_generics, lifetime) = buffer_lifetime(generics);
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut fields: Vec<Field> = variant.iter().map(Field::from_field).collect();
+    // Fields are bound to locals of the same name, which would hide the
+    // position the following fields are parsed from.
+    if fields.iter().any(|field| field.ident == "buffer") {
+        panic!("`buffer` is the current position, and can't be the name of a field");
+    }
+    check_conditional_dropped(&fields);
+
+    // A field marked `#[spans]` is given the bytes of every other field.
+    let (mut spans_start, mut spans_end) = (Tokens::new(), Tokens::new());
+    if let Some(spans) = fields.iter().find(|field| field.spans).map(|field| field.ident) {
+        spans_start = quote!(let mut __spans = Vec::new(););
+        spans_end = quote!(let #spans = ::decl::span::Spans::new(__spans););
+        for field in &mut fields {
+            field.record_span = !field.spans;
+        }
+    }
+
+    let names: Vec<&syn::Ident> = fields.iter().map(|field| field.ident).collect();
+    let values = names.clone();
+
+    // Offsets default to being relative to the start of the struct.
+    let mut table_start = Tokens::new();
+    if fields.iter().any(Field::uses_table_start) {
+        table_start.append("let");
+        table_start.append(field::TABLE_START);
+        table_start.append("= buffer;");
+    }
+
+    let mut arguments = None;
+    let mut validate = Tokens::new();
+    let mut expected_size = None;
+    let mut lazy = false;
+    for item in declarative_items(attrs) {
+        match *item {
+            syn::MetaItem::NameValue(ref name, syn::Lit::Str(ref args, _)) if name == "arguments" => {
+                arguments = Some(Arguments::from_str(args));
+            },
+            syn::MetaItem::NameValue(ref name, syn::Lit::Str(ref path, _)) if name == "validate" => {
+                validate.append(path);
+                validate.append("(&result).map_err(|error| error.at(__struct))?;");
+            },
+            syn::MetaItem::NameValue(ref name, syn::Lit::Int(size, _)) if name == "size" => {
+                expected_size = Some(size);
+            },
+            syn::MetaItem::Word(ref name) if name == "lazy" => lazy = true,
+            _ => panic!("unexpected `#[declarative(...)]` item on a struct: `{:?}`", item),
+        }
+    }
+
+    let static_size = derive_static_size(ident, generics, &fields, expected_size);
+    let layout = layout::derive_layout(ident, generics, &fields);
+    let lazy = match lazy {
+        true if arguments.is_some() => panic!("lazy structs can't take arguments"),
+        true => lazy::derive_lazy(vis, ident, generics, &lifetime, &fields),
+        false => Tokens::new(),
+    };
+
+    // Failures are located where the read which failed started, which is
+    // where `buffer` is left, unless a nested parser located them first.
+    let body = quote!(
+        let __struct = buffer;
+        let __result = (|| -> ::decl::declarative::DeclResult<#lifetime, Self> {
+            #table_start
+            #spans_start
+            #(#fields)*
+            #spans_end
+
+            let result = #ident { #(#names: #values),* };
+            #validate
+            Ok((result, buffer))
+        })();
+        __result.map_err(|error| error.at(buffer))
+    );
+
+    let arguments = match arguments {
+        Some(arguments) => arguments,
+        None => return quote!(
+            impl #impl_generics ::decl::declarative::Declarative<#lifetime> for #ident #ty_generics
+                #where_clause
+            {
+                fn parse(mut buffer: &#lifetime [u8]) -> ::decl::declarative::DeclResult<#lifetime, Self> {
+                    #body
+                }
+            }
+
+            #static_size
+            #layout
+            #lazy
+        ),
+    };
+
+    if arguments.args.iter().any(|arg| arg.ident == "buffer") {
+        panic!("`buffer` is the current position, and can't be the name of an argument");
+    }
+    let definition = arguments.definition();
+    let declaration = arguments.declaration();
+
+    quote!(
+        impl #impl_generics ::decl::declarative::DeclarativeWithArgs<#lifetime> for #ident #ty_generics
+            #where_clause
+        {
+            type Argument = #definition;
+
+            fn parse_with(
+                mut buffer: &#lifetime [u8],
+                argument: Self::Argument,
+            ) -> ::decl::declarative::DeclResult<#lifetime, Self> {
+                let #declaration = argument;
+                #body
+            }
+        }
+
+        #static_size
+        #layout
+        #lazy
+    )
+}
+
+// The preamble of a conditional field is only parsed when the condition
+// holds, so the values it drops don't exist for the fields after it.
+fn check_conditional_dropped(fields: &[Field]) {
+    for (index, field) in fields.iter().enumerate() {
+        if field.condition.is_none() {
+            continue;
+        }
+
+        for name in field.dropped_names() {
+            let later = fields[index + 1..].iter()
+                .find(|later| later.referenced_identifiers().contains(&name));
+            if let Some(later) = later {
+                panic!("`{}` is dropped before the conditional field `{}`, so it is only parsed \
+                        when the condition holds, and `{}` can't refer to it",
+                       name, field.ident, later.ident);
+            }
+        }
+    }
+}
+
+// Structs whose fields all have a size known before parsing implement
+// `StaticEncodingSize`, as long as the types of the fields do.  Each type is
+// bounded under a `for<'__size>` so that fields which aren't static remove
+// the impl rather than failing to compile.  `#[declarative(size = 12)]`
+// makes a mismatched or missing size a compile error.
+fn derive_static_size(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    fields: &[Field],
+    expected: Option<u64>,
+) -> Tokens
+{
+    let sizes: Option<Vec<Tokens>> = fields.iter()
+        .map(|field| Some(vec![field.preamble_size()?, field.value_size()?]))
+        .collect::<Option<Vec<_>>>()
+        .map(|sizes| sizes.into_iter().flat_map(|size| size).collect());
+    let sizes = match (sizes, expected) {
+        (Some(sizes), _) => sizes,
+        (None, Some(_)) => panic!("`{}` has fields whose size isn't known before parsing, \
+                                   so it can't have a `#[declarative(size = ...)]`", ident),
+        (None, None) => return Tokens::new(),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = &where_clause.predicates;
+    let types: Vec<Tokens> = fields.iter().flat_map(Field::static_types).collect();
+
+    let mut size = quote!(0 #(+ #sizes)*);
+    let mut assertion = Tokens::new();
+    if let Some(expected) = expected {
+        let expected = expected as usize;
+        let message = format!("`{}` isn't the {} bytes given by `#[declarative(size = ...)]`",
+            ident, expected);
+        size = quote!({
+            let size = #size;
+            assert!(size == #expected, #message);
+            size
+        });
+
+        // Generic structs are only checked once their size is used.
+        if generics.ty_params.is_empty() {
+            let lifetimes = generics.lifetimes.iter().map(|_| quote!('static));
+            assertion = quote!(
+                const _: usize = <#ident<#(#lifetimes),*> as ::decl::declarative::StaticEncodingSize>::SIZE;
+            );
+        }
+    }
+
+    quote!(
+        impl #impl_generics ::decl::declarative::StaticEncodingSize for #ident #ty_generics
+        where
+            #(#predicates,)*
+            #(for<'__size> #types: ::decl::declarative::StaticEncodingSize,)*
+        {
+            const SIZE: usize = #size;
+        }
+
+        #assertion
+    )
+}
+
+// Fieldless enums are parsed from their `#[repr(...)]` integer, which is read
+// as is or through the type given by `#[declarative(wire = "LeU16")]`.  A
+// variant marked `#[declarative(other)]` holds any value which doesn't match
+// the others, otherwise they're an `Error::InvalidEncoding`.
+fn derive_declarative_enum(
+    ident: &syn::Ident,
+    attrs: &[syn::Attribute],
+    variants: &[syn::Variant],
+) -> Tokens
+{
+    let mut repr = None;
+    for attr in attrs {
+        match attr.value {
+            syn::MetaItem::List(ref name, ref items) if name == "repr" => {
+                for item in items {
+                    match *item {
+                        syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ty)) => repr = Some(ty),
+                        _ => {},
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+    let repr = repr.expect("enums deriving `Declarative` require a `#[repr(<integer>)]`");
+
+    let mut wire = Tokens::new();
+    repr.to_tokens(&mut wire);
+    for item in declarative_items(attrs) {
+        match *item {
+            syn::MetaItem::NameValue(ref name, syn::Lit::Str(ref ty, _)) if name == "wire" => {
+                wire = Tokens::new();
+                wire.append(ty);
+            },
+            _ => panic!("unsupported attribute on enum `{}`", ident),
+        }
+    }
+
+    let mut arms = Vec::new();
+    let mut other = None;
+    let mut discriminant = quote!(0);
+    for variant in variants {
+        let is_other = declarative_items(&variant.attrs).iter().any(|item| match **item {
+            syn::MetaItem::Word(ref name) => name == "other",
+            _ => false,
+        });
+
+        let name = &variant.ident;
+        if is_other {
+            match variant.data {
+                syn::VariantData::Tuple(ref fields) if fields.len() == 1 => {},
+                _ => panic!("the `other` variant `{}` must hold the raw value, like `{}({})`",
+                            name, name, repr),
+            }
+            other = Some(quote!( other => #ident::#name(other), ));
+            // The `other` variant still takes a discriminant, which the
+            // variants after it count from.
+            if let Some(ref expr) = variant.discriminant {
+                discriminant = quote!(#expr);
+            }
+            discriminant = quote!((#discriminant) + 1);
+            continue;
+        }
+
+        match variant.data {
+            syn::VariantData::Unit => {},
+            _ => panic!("variant `{}` must not have fields, unless it is `#[declarative(other)]`", name),
+        }
+
+        if let Some(ref expr) = variant.discriminant {
+            discriminant = quote!(#expr);
+        }
+        arms.push(quote!( value if value == (#discriminant) => #ident::#name, ));
+        discriminant = quote!((#discriminant) + 1);
+    }
+
+    let other = other.unwrap_or_else(|| quote!(
+        _ => return Err(::decl::error::Error::InvalidEncoding),
+    ));
+
+    quote!(
+        impl<'buf> ::decl::declarative::Declarative<'buf> for #ident {
+            fn parse(buffer: &'buf [u8]) -> ::decl::declarative::DeclResult<'buf, Self> {
+                let (value, rest) = <#wire as ::decl::declarative::Declarative>::parse(buffer)?;
+                let value: #repr = ::std::convert::From::from(value);
+                let dest = match value {
+                    #(#arms)*
+                    #other
+                };
+                Ok((dest, rest))
+            }
+        }
+
+        impl ::decl::declarative::StaticEncodingSize for #ident {
+            const SIZE: usize = <#wire as ::decl::declarative::StaticEncodingSize>::SIZE;
+        }
+    )
+}
+
+#[test]
+#[should_panic(expected = "`count` is dropped before the conditional field `extra`")]
+fn conditional_dropped_values() {
+    let input = syn::parse_derive_input("
+        struct Versioned<'buf> {
+            version: u16,
+            #[dropped(count = \"u16\")]
+            #[declarative(condition = \"version >= 2\")]
+            extra: Option<u8>,
+            #[length = \"count\"]
+            values: Array<'buf, u8>,
+        }").unwrap();
+    let fields: Vec<Field> = match input.body {
+        syn::Body::Struct(ref data) => data.fields().iter().map(Field::from_field).collect(),
+        _ => unreachable!(),
+    };
+    check_conditional_dropped(&fields);
 }
@@ -0,0 +1,115 @@
+use quote::ToTokens;
+use quote::Tokens;
+
+use syn;
+
+// `ty` with `lifetime` replaced by `'static`, so that it may be named
+// outside of the struct.
+fn static_type(ty: &syn::Ty, lifetime: &syn::Lifetime) -> Tokens {
+    let mut tokens = Tokens::new();
+    ty.to_tokens(&mut tokens);
+
+    let mut result = Tokens::new();
+    for token in tokens.as_str().split_whitespace() {
+        match token == lifetime.ident.as_ref() {
+            true => result.append("'static"),
+            false => result.append(token),
+        }
+    }
+    result
+}
+
+// Structs generate `<Name>Owned`, with each field replaced by its
+// `IntoOwned::Owned`.  The lifetime of the buffer is the struct's first, and
+// a `#[spans]` field is left out, as its spans are only meaningful next to
+// the buffer.  `<Name>Owned` has only the attributes given by
+// `#[owned(...)]`, such as `#[owned(derive(Debug, Clone))]`, as not every
+// owned type implements the same traits.
+pub fn derive_owned_struct(
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    attrs: &[syn::Attribute],
+    fields: &[syn::Field],
+) -> Tokens
+{
+    if !generics.ty_params.is_empty() {
+        panic!("`IntoOwned` can't be derived for structs with type parameters");
+    }
+    let lifetime = generics.lifetimes.first().map(|def| def.lifetime.clone())
+        .unwrap_or_else(|| syn::Lifetime::new("'buf"));
+
+    let fields: Vec<&syn::Field> = fields.iter()
+        .filter(|field| !field.attrs.iter().any(|attr| attr.name() == "spans"))
+        .collect();
+    let names: Vec<&syn::Ident> = fields.iter()
+        .map(|field| field.ident.as_ref().expect("`IntoOwned` can't be derived for tuple structs"))
+        .collect();
+    let values = names.clone();
+    let sources = names.clone();
+    let visibilities = fields.iter().map(|field| &field.vis);
+    let types = fields.iter().map(|field| static_type(&field.ty, &lifetime));
+
+    let mut owned_attrs = Vec::new();
+    for attr in attrs {
+        match attr.value {
+            syn::MetaItem::List(ref name, ref items) if name == "owned" => {
+                for item in items {
+                    match *item {
+                        syn::NestedMetaItem::MetaItem(ref item) => owned_attrs.push(item),
+                        syn::NestedMetaItem::Literal(ref lit) =>
+                            panic!("unexpected literal `{:?}` in `#[owned(...)]`", lit),
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    let owned = syn::Ident::new(format!("{}Owned", ident));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote!(
+        #(#[#owned_attrs])*
+        #vis struct #owned {
+            #(#visibilities #names: <#types as ::decl::owned::IntoOwned>::Owned,)*
+        }
+
+        impl #impl_generics ::decl::owned::IntoOwned for #ident #ty_generics
+            #where_clause
+        {
+            type Owned = #owned;
+
+            fn into_owned(self) -> Result<#owned, ::decl::error::Error> {
+                Ok(#owned {
+                    #(#values: ::decl::owned::IntoOwned::into_owned(self.#sources)?,)*
+                })
+            }
+        }
+    )
+}
+
+// Enums don't borrow the buffer, so are their own owned form.
+pub fn derive_owned_enum(ident: &syn::Ident, generics: &syn::Generics) -> Tokens {
+    if !generics.lifetimes.is_empty() || !generics.ty_params.is_empty() {
+        panic!("`IntoOwned` can only be derived for enums without generics");
+    }
+
+    quote!(
+        impl ::decl::owned::IntoOwned for #ident {
+            type Owned = #ident;
+
+            fn into_owned(self) -> Result<#ident, ::decl::error::Error> {
+                Ok(self)
+            }
+        }
+    )
+}
+
+#[test]
+fn static_types() {
+    let ty = syn::parse_type("Offset16<'buf, Array<'buf, u16>>").unwrap();
+    assert_eq!(
+        "Offset16 < 'static , Array < 'static , u16 > >",
+        static_type(&ty, &syn::Lifetime::new("'buf")).as_str());
+}
@@ -1,12 +1,42 @@
+// Structs whose fields all have a static size implement
+// `StaticEncodingSize`, and `size` checks it against the spec at compile
+// time.  `Thing` has arrays, so it couldn't take one.
+//   #[declarative(size = 12)]
+//
+// Every field (and dropped field) before the first of unknown size gets an
+// `OFFSET_<NAME>` constant.  A derive can't document the struct itself, so
+// the layout table documents the first impl of the constants, and shows
+// above them on the struct's rustdoc page rather than in its summary.
+//
+// `lazy` also generates `ThingView<'buf>`, with a getter per field that
+// reads it on demand.  Parsing the view checks tags and finds where each
+// field after one of unknown size starts, so getters don't reparse.
+//   #[declarative(lazy)]
+//
+// `#[derive(IntoOwned)]` generates `ThingOwned`, which doesn't borrow the
+// buffer: arrays become `Vec`s, strings `String`s, offsets `Box`es (or
+// `Option<Box<T>>` for `NullableOffset16`) and byte slices `Vec<u8>`s.  The
+// `Spans` field is left out, and `ThingOwned` only gets the attributes in
+// `#[owned(...)]`.
+//   #[owned(derive(Debug, Clone))]
+//
+// Errors are located where the read which failed started, even inside a
+// nested struct, and `Error::position` finds the offset within the buffer.
+// A failed check is located at its field.
+//
+// Arguments make the type `DeclarativeWithArgs`.  One argument is passed as
+// itself, more as a tuple.  `buffer` names the current position, so it
+// can't be an argument or a field.
 #[derive(Declarative)]
-#[declarative(arguments = "buffer: &'buf [u8]")]
+#[declarative(arguments = "other_table: &'buf [u8], thing: u32")]
 pub struct Thing<'buf> {
     // Tags validate a match.  If there isn't a match,
     // the parser will return with an Error::InvalidTag
     #[tag("utf8 string")]   // tags can be strings
     #[tag(0x0001_0000u32)]  // tags can be a suffixed integer
-    #[tag(b"cmap")]         // tags can be a byte str
+    #[tag = b"cmap"]        // tags can be a byte str (name-value form only)
     #[tag('k')]             // tags can be a character
+    #[tag(CMAP)]            // tags can be a constant, like a `Tag4`
 
     // Tags can match one of a number of options.
     #[tag(0x0001_0000u32, b"OTTO")]
@@ -20,6 +50,21 @@ pub struct Thing<'buf> {
     #[length = "num_tables")]
     #[length = 256]
     must_be_array: Array<'buf, T>,
+
+    // Small tables can be read eagerly: a `Vec` with a length is parsed
+    // as an `Array` and collected, and a `Box` is read through the offset
+    // type given and resolved while parsing.  A `Vec` with an `offset` is
+    // an array of offsets, each resolved into the `Vec`.
+    #[length = "num_tables"]
+    vec: Vec<T>,
+    #[offset = "Offset16"]
+    boxed: Box<T>,
+
+    // Arrays whose elements each need their own argument take a closure of
+    // the element index and the buffer starting at that element.
+    #[length = "num_tables"]
+    #[element_argument = "move |index, position| (index, position)"]
+    indexed_array: IndexedArray<'buf, T>,
     
     // Offsets can be relative to:
     // 1. The beginning of the table (default, no attributes)
@@ -29,8 +74,62 @@ pub struct Thing<'buf> {
     // 3. The current position (which is named buffer)
     #[relative_to(buffer = "&'buf [u8]")]
     other_table: Offset32<'buf, OtherTable>,
+
+    // Arrays of offsets pass the same base to every element.
+    #[length = "num_tables"]
+    table_offsets: Array<'buf, Offset16<'buf, Table>>,
     
-    // Some tables may require an argument to be parsed
+    // Fields which only exist when a condition holds are an `Option`, and
+    // consume nothing when it doesn't.  syn can't read `if` as an attribute
+    // name, so the condition is named `condition`.  Its `skip`, `reserved`,
+    // `pad_to`, `tag` and `dropped` attributes only apply when it holds too,
+    // so it's an error for a later field to refer to a value dropped there.
+    #[declarative(condition = "version >= 2")]
+    x_height: Option<i16>,
+
+    // Bytes before a field can be skipped, must be zero, or padding up to
+    // an alignment from the start of the struct or a given buffer.
+    #[skip(2)]
+    #[reserved(4)]
+    #[pad_to(4)]
+    #[pad_to(512, relative_to = "file")]
+    aligned: u32,
+
+    // Fields can be confined to a number of bytes, after which parsing
+    // continues however many the field used.  `strict_size` also fails if
+    // the field leaves some unused.
+    #[size = "length - 8"]
+    #[strict_size = 16]
+    record: Record<'buf>,
+
+    // Raw bytes: `&'buf [u8]` takes its length as an argument, `#[rest]`
+    // takes everything left, and `#[raw = "Type"]` parses a `Type` but keeps
+    // only the bytes it used.  `Raw<'buf, Type>` keeps both.
+    #[raw = "Header<'buf>"]
+    header_bytes: &'buf [u8],
+    #[rest]
+    data: &'buf [u8],
+
+    // A `Spans` field isn't parsed, but records the bytes each of the other
+    // fields was parsed from.  `decl::span` turns them into positions.
+    #[spans]
+    spans: Spans<'buf>,
+
+    // Values can be checked once parsed.  Ranges and `must_be` fail with an
+    // `Error::InvalidValue` naming the field and giving the value, which
+    // they compare as an `i128`, so they also work on wrappers like
+    // `BeU16`.  Ranges are strings, as syn can't read them in an
+    // attribute.  `validate` takes a `fn(&T) -> Result<(), Error>` here, or
+    // a `fn(&Self) -> Result<(), Error>` on the struct.
+    #[range = "16..=16384"]
+    units_per_em: u16,
+    #[must_be(0)]
+    reserved: u16,
+    #[declarative(validate = "is_even")]
+    seg_count_x2: u16,
+
+    // Some tables may require an argument to be parsed.  Arguments may be
+    // arguments of the struct, earlier fields or dropped fields.
     #[argument(thing = "u32", buffer = "&'buf [u8]")]
     foo: Bar<'buf>,
 }
@@ -1,5 +1,8 @@
-use syn::NestedMetaItem;
+use syn::Ident;
+use syn::IntTy;
 use syn::Lit;
+use syn::MetaItem;
+use syn::NestedMetaItem;
 
 use quote::ToTokens;
 use quote::Tokens;
@@ -7,24 +10,131 @@ use quote::Tokens;
 // Actived from
 //   MetaItem::List("tag", Vec<NestedMetaItem>)
 //   MetaItem::NameValue("tag", Lit)
+//
+// The list form can't hold a byte string, as syn reads `b` as a word, so
+// byte strings must use the name-value form, `#[tag = b"cmap"]`.
+pub struct Tags<'a>(Vec<TagValue<'a>>);
 
-pub enum Tags<'a> {
-    One(Lit),
-    Many(&'a [NestedMetaItem]),
+// A tag is either a literal, or the name of a constant implementing `Tag`
+// (such as a `Tag4`).
+enum TagValue<'a> {
+    Lit(&'a Lit),
+    Const(&'a Ident),
+}
+
+impl<'a> Tags<'a> {
+    pub fn from_meta_item(item: &MetaItem) -> Tags {
+        match *item {
+            MetaItem::NameValue(_, ref lit) => Tags(vec![TagValue::Lit(lit)]),
+            MetaItem::List(_, ref items) if !items.is_empty() => {
+                Tags(items.iter().map(|item| match *item {
+                    NestedMetaItem::Literal(ref lit) => TagValue::Lit(lit),
+                    NestedMetaItem::MetaItem(MetaItem::Word(ref ident)) => TagValue::Const(ident),
+                    _ => panic!("tags must be literals or the names of constants"),
+                }).collect())
+            },
+            _ => panic!("tags must be either `#[tag(<value>, ...)]` or `#[tag = <value>]`"),
+        }
+    }
+
+    // The values as written, for documentation.
+    pub fn describe(&self) -> String {
+        let values: Vec<String> = self.0.iter().map(|value| match *value {
+            TagValue::Lit(lit) => quote!(#lit).as_str().to_string(),
+            TagValue::Const(ident) => ident.to_string(),
+        }).collect();
+        values.join(" | ")
+    }
+
+    // The number of bytes the tag takes, when every value is a literal of
+    // the same size.
+    pub fn static_size(&self) -> Option<usize> {
+        let mut sizes = self.0.iter().map(|value| match *value {
+            TagValue::Lit(&Lit::Str(ref s, _)) => Some(s.len()),
+            TagValue::Lit(&Lit::ByteStr(ref bytes, _)) => Some(bytes.len()),
+            TagValue::Lit(&Lit::Byte(_)) => Some(1),
+            TagValue::Lit(&Lit::Char(c)) => Some(c.len_utf8()),
+            TagValue::Lit(&Lit::Int(_, IntTy::U8)) | TagValue::Lit(&Lit::Int(_, IntTy::I8)) => Some(1),
+            TagValue::Lit(&Lit::Int(_, IntTy::U16)) | TagValue::Lit(&Lit::Int(_, IntTy::I16)) => Some(2),
+            TagValue::Lit(&Lit::Int(_, IntTy::U32)) | TagValue::Lit(&Lit::Int(_, IntTy::I32)) => Some(4),
+            TagValue::Lit(&Lit::Int(_, IntTy::U64)) | TagValue::Lit(&Lit::Int(_, IntTy::I64)) => Some(8),
+            _ => None,
+        });
+
+        let first = sizes.next().and_then(|size| size)?;
+        match sizes.all(|size| size == Some(first)) {
+            true => Some(first),
+            false => None,
+        }
+    }
 }
 
 fn lit_validate(lit: &Lit, tokens: &mut Tokens) {
+    tokens.append("::decl::declarative::Tag::validate(");
     match *lit {
-        Str(ref s, _) => quote!( lit ),
-        ByteStr(ref b, _) => quote! ( lit ),
-        Byte(b) => {
-            tokens.append(&format!(
+        Lit::Str(..) => lit.to_tokens(tokens),
+        Lit::ByteStr(..) => {
+            tokens.append("&");
+            lit.to_tokens(tokens);
+            tokens.append("[..]");
+        },
+        Lit::Int(_, IntTy::Unsuffixed) =>
+            panic!("integer tags must have a suffix to give their size, e.g. `0x00010000u32`"),
+        Lit::Byte(..) | Lit::Char(..) | Lit::Int(..) => {
+            tokens.append("&");
+            lit.to_tokens(tokens);
+        },
+        _ => panic!("tags must be a string, byte string, byte, char or integer. Got: `{:?}`", lit),
+    }
+    tokens.append(", buffer)");
+}
 
-            ))
-        }
+fn value_validate(value: &TagValue, tokens: &mut Tokens) {
+    match *value {
+        TagValue::Lit(lit) => lit_validate(lit, tokens),
+        TagValue::Const(ident) => {
+            tokens.append("::decl::declarative::Tag::validate(&");
+            ident.to_tokens(tokens);
+            tokens.append(", buffer)");
+        },
+    }
+}
+
+// Each value is tried in turn, and the error of the last is returned if
+// none of them match.
+fn values_validate(values: &[TagValue], tokens: &mut Tokens) {
+    match values.split_first() {
+        Some((value, [])) => {
+            value_validate(value, tokens);
+            tokens.append("?");
+        },
+        Some((value, rest)) => {
+            tokens.append("match");
+            value_validate(value, tokens);
+            tokens.append("{ Ok(rest) => rest, Err(_) =>");
+            values_validate(rest, tokens);
+            tokens.append("}");
+        },
+        None => unreachable!(),
     }
 }
 
 impl<'a> ToTokens for Tags<'a> {
-    fn to_tokens(&self, tokens: &mut Tokens) {}
+    fn to_tokens(&self, tokens: &mut Tokens) {
+        tokens.append("buffer =");
+        values_validate(&self.0, tokens);
+        tokens.append(";");
+    }
+}
+
+#[test]
+fn tags_printing() {
+    let lits = [Lit::Int(0x00010000, IntTy::U32), Lit::ByteStr(b"OTTO".to_vec(), ::syn::StrStyle::Cooked)];
+    let tags = Tags(lits.iter().map(TagValue::Lit).collect());
+    assert_eq!(
+        "buffer = match ::decl::declarative::Tag::validate( & 65536u32 , buffer) \
+         { Ok(rest) => rest, Err(_) => \
+         ::decl::declarative::Tag::validate( & b\"OTTO\" [..] , buffer) ? } ;",
+        quote!(#tags).as_str());
+    assert_eq!(Some(4), tags.static_size());
 }